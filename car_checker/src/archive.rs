@@ -0,0 +1,140 @@
+//! Reading submitted zips.
+//!
+//! Submissions come straight from Discord, so nothing about them is trusted: not
+//! the file name, not the number of entries, not the sizes in the headers and not
+//! the paths inside. Nothing is extracted to disk. Every entry's path is checked,
+//! and only CSV files are decompressed, in memory and never past the size limits.
+//!
+//! A zip can hold any number of CSVs in any folder, so [`find_csvs`] returns all of
+//! them and [`choose`] picks one, or explains which ones there are to pick from.
+
+use std::io::{Cursor, Read};
+
+use crate::ArchiveError;
+
+/// How much a submitted zip may contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Size of the zip itself, in bytes.
+    pub zip_size: u64,
+    /// Number of files and folders in the zip.
+    pub entries: usize,
+    /// Uncompressed size of a single CSV, in bytes.
+    pub csv_size: u64,
+    /// Uncompressed size of every CSV together, in bytes.
+    pub total_size: u64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        // An export is a few hundred KB at most
+        Self { zip_size: 8 * 1024 * 1024, entries: 64, csv_size: 4 * 1024 * 1024, total_size: 16 * 1024 * 1024 }
+    }
+}
+
+/// Checks that an uploaded file name is a plain `something.zip`, so it can't be used to point
+/// anywhere on disk.
+pub fn check_file_name(name: &str) -> Result<(), ArchiveError> {
+    let stem = name.len().checked_sub(4).filter(|end| name.is_char_boundary(*end)).map(|end| (&name[..end], &name[end..]));
+    let valid = match stem {
+        Some((stem, extension)) => {
+            extension.eq_ignore_ascii_case(".zip")
+                && !stem.is_empty()
+                && name.len() <= 128
+                && !name.starts_with('.')
+                && !name.chars().any(|c| c == '/' || c == '\\' || c.is_control())
+        },
+        None => false,
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(ArchiveError::BadFileName { name: String::from(name) })
+    }
+}
+
+/// A CSV file found in a zip, which may or may not be an export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    /// Where it is in the zip, like `My Car/My Car.csv`.
+    pub path: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Reads every CSV in a zip, in whichever folder they are, in the order they're stored in.
+/// Leftovers from macOS (`__MACOSX/`, `._My Car.csv`) are skipped.
+pub fn find_csvs(zip_bytes: &[u8], limits: &Limits) -> Result<Vec<Candidate>, ArchiveError> {
+    if zip_bytes.len() as u64 > limits.zip_size {
+        return Err(ArchiveError::TooLarge { size: zip_bytes.len() as u64, limit: limits.zip_size });
+    }
+    let mut archive = zip::ZipArchive::new(Cursor::new(zip_bytes)).map_err(unreadable)?;
+    if archive.len() > limits.entries {
+        return Err(ArchiveError::TooManyEntries { entries: archive.len(), limit: limits.entries });
+    }
+
+    // Check every path before reading anything
+    let mut csvs = Vec::new();
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i).map_err(unreadable)?;
+        let path = match entry.enclosed_name() {
+            Some(path) => path.to_path_buf(),
+            None => return Err(ArchiveError::UnsafePath { path: String::from(entry.name()) }),
+        };
+        let is_csv = path.extension().and_then(|extension| extension.to_str()).is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+        let is_junk = path.components().any(|component| component.as_os_str() == "__MACOSX")
+            || path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with("._"));
+        if !entry.is_dir() && is_csv && !is_junk {
+            csvs.push(i);
+        }
+    }
+
+    let mut candidates = Vec::new();
+    let mut total_size = 0;
+    for i in csvs {
+        let mut entry = archive.by_index(i).map_err(unreadable)?;
+        if entry.size() > limits.csv_size {
+            return Err(ArchiveError::TooLarge { size: entry.size(), limit: limits.csv_size });
+        }
+        // The header's size can't be trusted, so stop reading right after the limit
+        let mut bytes = Vec::new();
+        (&mut entry).take(limits.csv_size + 1).read_to_end(&mut bytes).map_err(|e| ArchiveError::Unreadable { reason: e.to_string() })?;
+        if bytes.len() as u64 > limits.csv_size {
+            return Err(ArchiveError::TooLarge { size: bytes.len() as u64, limit: limits.csv_size });
+        }
+        total_size += bytes.len() as u64;
+        if total_size > limits.total_size {
+            return Err(ArchiveError::TooLarge { size: total_size, limit: limits.total_size });
+        }
+        candidates.push(Candidate { path: String::from(entry.name()), bytes });
+    }
+    Ok(candidates)
+}
+
+/// Picks the export out of the CSVs in a zip. Without a `choice` there has to be exactly one;
+/// otherwise `choice` is its number in the list (starting at 1), its path or its file name.
+pub fn choose(candidates: Vec<Candidate>, choice: Option<&str>) -> Result<Candidate, ArchiveError> {
+    if candidates.is_empty() {
+        return Err(ArchiveError::NoCsv);
+    }
+    let paths = || candidates.iter().map(|candidate| candidate.path.clone()).collect::<Vec<_>>();
+    let index = match choice {
+        None if candidates.len() == 1 => 0,
+        None => return Err(ArchiveError::SeveralCsvs { paths: paths() }),
+        Some(choice) => {
+            let choice = choice.trim();
+            let found = match choice.parse::<usize>() {
+                Ok(number) => number.checked_sub(1).filter(|index| *index < candidates.len()),
+                Err(_) => candidates.iter().position(|candidate| {
+                    let file_name = candidate.path.rsplit('/').next().unwrap_or_default();
+                    candidate.path.eq_ignore_ascii_case(choice) || file_name.eq_ignore_ascii_case(choice)
+                }),
+            };
+            found.ok_or_else(|| ArchiveError::NoSuchCsv { choice: String::from(choice), paths: paths() })?
+        },
+    };
+    Ok(candidates.into_iter().nth(index).unwrap())
+}
+
+fn unreadable(e: zip::result::ZipError) -> ArchiveError {
+    ArchiveError::Unreadable { reason: e.to_string() }
+}
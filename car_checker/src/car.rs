@@ -0,0 +1,337 @@
+//! A typed view of a car export.
+//!
+//! [`Car`] groups the columns the regulations care about into sections, so rules
+//! can be written against fields instead of repeating column names. Every field
+//! is a [`Column`] that remembers its name and either its parsed value or why it
+//! couldn't be read, so a single missing column only breaks the rules that use it.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{CarData, CheckError};
+
+/// A single column of the export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column<T> {
+    pub name: String,
+    value: Result<T, CheckError>,
+}
+
+impl<T: Clone> Column<T> {
+    fn read<F: FnOnce(&CarData, &str) -> Result<T, CheckError>>(car_data: &CarData, name: &str, parse: F) -> Self {
+        Self { name: String::from(name), value: parse(car_data, name) }
+    }
+
+    /// The parsed value, or why it couldn't be read.
+    pub fn get(&self) -> Result<T, CheckError> {
+        self.value.clone()
+    }
+}
+
+fn text(car_data: &CarData, name: &str) -> Column<String> {
+    Column::read(car_data, name, |car_data, name| car_data.get(name))
+}
+
+fn number(car_data: &CarData, name: &str) -> Column<f32> {
+    Column::read(car_data, name, |car_data, name| car_data.get_f32(name))
+}
+
+fn whole(car_data: &CarData, name: &str) -> Column<usize> {
+    Column::read(car_data, name, |car_data, name| car_data.get_usize(name))
+}
+
+fn parsed<T: Clone + FromStr<Err = String>>(car_data: &CarData, name: &str) -> Column<T> {
+    Column::read(car_data, name, |car_data, name| {
+        let value = car_data.get(name)?;
+        value.parse().map_err(|reason| CheckError::Malformed { field: String::from(name), value, reason })
+    })
+}
+
+/// A group of columns that differs between exports, like the techpools or quality sliders.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Columns<T>(Vec<Column<T>>);
+
+impl<T: Clone> Columns<T> {
+    /// Every column whose name contains all of the given substrings, sorted by name.
+    fn containing<F: Fn(&CarData, &str) -> Result<T, CheckError>>(car_data: &CarData, parts: &[&str], parse: F) -> Self {
+        Self(car_data.keys_containing(parts).iter().map(|name| Column::read(car_data, name, &parse)).collect())
+    }
+
+    /// The value of a column by name, `Missing` if the export doesn't have it.
+    pub fn get(&self, name: &str) -> Result<T, CheckError> {
+        match self.0.iter().find(|column| column.name == name) {
+            Some(column) => column.get(),
+            None => Err(CheckError::Missing { field: String::from(name) }),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Column<T>> {
+        self.0.iter()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Aspiration {
+    None,
+    NaturallyAspirated,
+    Turbo,
+    TwinTurbo,
+    QuadTurbo,
+    Other(String),
+}
+
+impl FromStr for Aspiration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "None" => Aspiration::None,
+            s if s.contains("Quad") => Aspiration::QuadTurbo,
+            s if s.contains("Twin") => Aspiration::TwinTurbo,
+            s if s.contains("Natural") => Aspiration::NaturallyAspirated,
+            "Turbo" => Aspiration::Turbo,
+            s => Aspiration::Other(String::from(s)),
+        })
+    }
+}
+
+impl fmt::Display for Aspiration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Aspiration::None => f.write_str("None"),
+            Aspiration::NaturallyAspirated => f.write_str("Naturally Aspirated"),
+            Aspiration::Turbo => f.write_str("Turbo"),
+            Aspiration::TwinTurbo => f.write_str("Twin Turbo"),
+            Aspiration::QuadTurbo => f.write_str("Quad Turbo"),
+            Aspiration::Other(name) => f.write_str(name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Gearbox {
+    Manual,
+    Automatic,
+    AdvancedAutomatic,
+    AutoManual,
+    Other(String),
+}
+
+impl FromStr for Gearbox {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "Manual" => Gearbox::Manual,
+            "Automatic" => Gearbox::Automatic,
+            s if s.contains("Adv") => Gearbox::AdvancedAutomatic,
+            s if s.contains("Auto Manual") => Gearbox::AutoManual,
+            s => Gearbox::Other(String::from(s)),
+        })
+    }
+}
+
+impl fmt::Display for Gearbox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Gearbox::Manual => f.write_str("Manual"),
+            Gearbox::Automatic => f.write_str("Automatic"),
+            Gearbox::AdvancedAutomatic => f.write_str("Adv Automatic"),
+            Gearbox::AutoManual => f.write_str("Auto Manual"),
+            Gearbox::Other(name) => f.write_str(name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Differential {
+    Open,
+    GearedLsd,
+    ViscousLsd,
+    ElectronicLsd,
+    Locked,
+    Other(String),
+}
+
+impl FromStr for Differential {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "Open" => Differential::Open,
+            "Geared LSD" => Differential::GearedLsd,
+            "Viscous LSD" => Differential::ViscousLsd,
+            "Electronic LSD" => Differential::ElectronicLsd,
+            "Locked" => Differential::Locked,
+            s => Differential::Other(String::from(s)),
+        })
+    }
+}
+
+impl fmt::Display for Differential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Differential::Open => f.write_str("Open"),
+            Differential::GearedLsd => f.write_str("Geared LSD"),
+            Differential::ViscousLsd => f.write_str("Viscous LSD"),
+            Differential::ElectronicLsd => f.write_str("Electronic LSD"),
+            Differential::Locked => f.write_str("Locked"),
+            Differential::Other(name) => f.write_str(name),
+        }
+    }
+}
+
+/// Read from the `Leaded Fuel` column, which is `TRUE` or `FALSE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fuel {
+    Leaded,
+    Unleaded,
+}
+
+impl FromStr for Fuel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "TRUE" => Ok(Fuel::Leaded),
+            "FALSE" => Ok(Fuel::Unleaded),
+            _ => Err(String::from("expected TRUE or FALSE")),
+        }
+    }
+}
+
+impl fmt::Display for Fuel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fuel::Leaded => f.write_str("TRUE"),
+            Fuel::Unleaded => f.write_str("FALSE"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Engine {
+    pub family_year: Column<usize>,
+    pub variant_year: Column<usize>,
+    pub cylinders: Column<usize>,
+    pub family_displacement: Column<f32>,
+    pub variant_displacement: Column<f32>,
+    pub intake: Column<String>,
+    pub headers: Column<String>,
+    pub mufflers: [Column<String>; 2],
+    pub fuel: Column<Fuel>,
+    pub octane: Column<usize>,
+    pub reliability: Column<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AspirationSetup {
+    pub kind: Column<Aspiration>,
+    pub quality: Column<f32>,
+    pub peak_boost: Column<f32>,
+    pub compressor_size: Column<f32>,
+    pub turbine_size: Column<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chassis {
+    pub material: Column<String>,
+    pub front_suspension: Column<String>,
+    pub rear_suspension: Column<String>,
+    pub gearbox: Column<Gearbox>,
+    pub differential: Column<Differential>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Body {
+    pub name: Column<String>,
+    pub model_year: Column<usize>,
+    pub trim_year: Column<usize>,
+    pub panel_material: Column<String>,
+    pub small_seats: [Column<usize>; 3],
+    pub active_aero: Column<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrimStats {
+    pub safety: Column<f32>,
+    pub economy: Column<f32>,
+    pub front_downforce: Column<f32>,
+    pub rear_downforce: Column<f32>,
+}
+
+/// A car export, with the columns the regulations use grouped by section.
+#[derive(Debug, Clone)]
+pub struct Car {
+    pub engine: Engine,
+    pub aspiration: AspirationSetup,
+    pub chassis: Chassis,
+    pub body: Body,
+    pub stats: TrimStats,
+    pub techpools: Columns<usize>,
+    pub quality: Columns<f32>,
+    columns: CarData,
+}
+
+impl From<&CarData> for Car {
+    fn from(car_data: &CarData) -> Self {
+        Self {
+            engine: Engine {
+                family_year: whole(car_data, "Engine Family Year"),
+                variant_year: whole(car_data, "Variant Year"),
+                cylinders: whole(car_data, "Cylinder Count"),
+                family_displacement: number(car_data, "Family Displacement"),
+                variant_displacement: number(car_data, "Variant Displacement"),
+                intake: text(car_data, "Intake"),
+                headers: text(car_data, "Headers"),
+                mufflers: [text(car_data, "Muffler 1"), text(car_data, "Muffler 2")],
+                fuel: parsed(car_data, "Leaded Fuel"),
+                octane: whole(car_data, "Fuel Octane"),
+                reliability: number(car_data, "Engine Reliability"),
+            },
+            aspiration: AspirationSetup {
+                kind: parsed(car_data, "Aspiration"),
+                quality: number(car_data, "Variant Aspiration Quality"),
+                peak_boost: number(car_data, "Peak Boost"),
+                compressor_size: number(car_data, "Compressor Size 1"),
+                turbine_size: number(car_data, "Turbine Size 1"),
+            },
+            chassis: Chassis {
+                material: text(car_data, "Chassis Material"),
+                front_suspension: text(car_data, "Front Suspension"),
+                rear_suspension: text(car_data, "Rear Suspension"),
+                gearbox: parsed(car_data, "Gearbox Type"),
+                differential: parsed(car_data, "Differential Type"),
+            },
+            body: Body {
+                name: text(car_data, "Body Name"),
+                model_year: whole(car_data, "Model Year"),
+                trim_year: whole(car_data, "Trim Year"),
+                panel_material: text(car_data, "Panel Material"),
+                small_seats: [whole(car_data, "Small 1st Row Seats"), whole(car_data, "Small 2nd Row Seats"), whole(car_data, "Small 3rd Row Seats")],
+                active_aero: text(car_data, "Active Aero"),
+            },
+            stats: TrimStats {
+                safety: number(car_data, "Safety Rating"),
+                economy: number(car_data, "Trim Economy"),
+                front_downforce: number(car_data, "Front Downforce"),
+                rear_downforce: number(car_data, "Rear Downforce"),
+            },
+            techpools: Columns::containing(car_data, &["Techpool"], |car_data, name| car_data.get_usize(name)),
+            quality: Columns::containing(car_data, &["Quality"], |car_data, name| car_data.get_f32(name)),
+            columns: car_data.clone(),
+        }
+    }
+}
+
+impl Car {
+    /// The engine quality sliders, which are the variant's.
+    pub fn engine_quality(&self) -> impl Iterator<Item = &Column<f32>> {
+        self.quality.iter().filter(|column| column.name.contains("Variant"))
+    }
+
+    /// Finds the column (and its value) that uses the given part, if any.
+    pub fn find_part(&self, part_name: &str) -> Option<(&String, &String)> {
+        self.columns.find_part(part_name)
+    }
+}
@@ -0,0 +1,215 @@
+//! Differences between two versions of a car.
+//!
+//! [`CarDiff::between`] compares every column of two exports: columns that were
+//! added, removed or changed, with the change in value for numeric columns. The
+//! fields are grouped into the sections of the game's designer so the EMs can see
+//! at a glance what an entrant touched, and the diff renders to Markdown, JSON and
+//! to plain `(name, value, inline)` tuples for the fields of a Discord embed.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::CarData;
+
+/// Where a column belongs in the car designer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Section {
+    Engine,
+    Chassis,
+    Aero,
+    /// The body, interior, fixtures and everything else on the trim.
+    Trim,
+}
+
+impl Section {
+    pub const ALL: [Section; 4] = [Section::Engine, Section::Chassis, Section::Aero, Section::Trim];
+
+    /// The section a column falls under, going by its name.
+    pub fn of(field: &str) -> Self {
+        const AERO: &[&str] = &["Downforce", "Aero", "Wing", "Spoiler", "Drag"];
+        const ENGINE: &[&str] = &["Cylinder", "Displacement", "Aspiration", "Intake", "Headers", "Muffler", "Boost", "Compressor", "Turbine", "Fuel", "Octane", "Bore", "Stroke", "RPM"];
+        const CHASSIS: &[&str] = &["Chassis", "Suspension", "Gearbox", "Differential", "Drivetrain", "Brake", "Tyre", "Tire", "Rim", "Spring", "Damper", "Gear"];
+        let contains = |words: &[&str]| words.iter().any(|word| field.contains(word));
+        if contains(AERO) {
+            Section::Aero
+        } else if ["Engine", "Family", "Variant"].iter().any(|prefix| field.starts_with(prefix)) || contains(ENGINE) {
+            Section::Engine
+        } else if contains(CHASSIS) {
+            Section::Chassis
+        } else {
+            Section::Trim
+        }
+    }
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Section::Engine => "Engine",
+            Section::Chassis => "Chassis",
+            Section::Aero => "Aero",
+            Section::Trim => "Trim",
+        };
+        f.write_str(name)
+    }
+}
+
+/// How much a numeric column changed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Delta {
+    pub absolute: f64,
+    /// Relative to the old value, `None` if that was 0.
+    pub percent: Option<f64>,
+}
+
+impl Delta {
+    fn between(old: &str, new: &str) -> Option<Self> {
+        let old = old.trim().parse::<f64>().ok()?;
+        let new = new.trim().parse::<f64>().ok()?;
+        let percent = if old == 0.0 { None } else { Some((new - old) / old.abs() * 100.0) };
+        Some(Self { absolute: new - old, percent })
+    }
+}
+
+impl fmt::Display for Delta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.absolute >= 0.0 { "+" } else { "" };
+        write!(f, "{}{}", sign, number(self.absolute))?;
+        if let Some(percent) = self.percent {
+            write!(f, ", {}{:.1}%", sign, percent)?;
+        }
+        Ok(())
+    }
+}
+
+/// A number without float noise or trailing zeros, e.g. `0.3` instead of `0.30000000000000004`.
+fn number(value: f64) -> String {
+    let formatted = format!("{:.3}", value);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+    if trimmed == "-0" { String::from("0") } else { String::from(trimmed) }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Change {
+    /// The column is only in the new version.
+    Added { value: String },
+    /// The column is only in the old version.
+    Removed { value: String },
+    /// `delta` is set if both values are numbers.
+    Changed { old: String, new: String, delta: Option<Delta> },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Added { value } => write!(f, "added: {}", value),
+            Change::Removed { value } => write!(f, "removed (was {})", value),
+            Change::Changed { old, new, delta: Some(delta) } => write!(f, "{} → {} ({})", old, new, delta),
+            Change::Changed { old, new, delta: None } => write!(f, "{} → {}", old, new),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldDiff {
+    pub field: String,
+    pub section: Section,
+    #[serde(flatten)]
+    pub change: Change,
+}
+
+/// Every column that differs between two versions of a car, sorted by section and then by name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CarDiff {
+    pub fields: Vec<FieldDiff>,
+}
+
+impl CarDiff {
+    pub fn between(old: &CarData, new: &CarData) -> Self {
+        let mut fields = Vec::new();
+        for (field, old_value) in old.iter() {
+            let change = match new.get(field.as_str()) {
+                Err(_) => Change::Removed { value: old_value.clone() },
+                Ok(new_value) if new_value != *old_value => Change::Changed { delta: Delta::between(old_value, &new_value), old: old_value.clone(), new: new_value },
+                Ok(_) => continue,
+            };
+            fields.push(FieldDiff { field: field.clone(), section: Section::of(field), change });
+        }
+        for (field, new_value) in new.iter().filter(|(field, _)| !old.contains_key(*field)) {
+            fields.push(FieldDiff { field: field.clone(), section: Section::of(field), change: Change::Added { value: new_value.clone() } });
+        }
+        fields.sort_by(|a, b| (a.section, &a.field).cmp(&(b.section, &b.field)));
+        Self { fields }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn get(&self, field: &str) -> Option<&FieldDiff> {
+        self.fields.iter().find(|diff| diff.field == field)
+    }
+
+    /// The changed fields of a section.
+    pub fn section(&self, section: Section) -> impl Iterator<Item = &FieldDiff> {
+        self.fields.iter().filter(move |diff| diff.section == section)
+    }
+
+    /// A Markdown table per section that has changes.
+    pub fn to_markdown(&self) -> String {
+        if self.is_empty() {
+            return String::from("No changes.\n");
+        }
+        let escape = |value: &str| value.replace('|', "\\|");
+        let mut markdown = String::new();
+        for section in Section::ALL {
+            let mut fields = self.section(section).peekable();
+            if fields.peek().is_none() {
+                continue;
+            }
+            markdown.push_str(&format!("## {}\n\n| Field | Old | New | Change |\n|---|---|---|---|\n", section));
+            for diff in fields {
+                let (old, new, change) = match &diff.change {
+                    Change::Added { value } => ("", value.as_str(), String::from("added")),
+                    Change::Removed { value } => (value.as_str(), "", String::from("removed")),
+                    Change::Changed { old, new, delta } => (old.as_str(), new.as_str(), delta.map(|delta| delta.to_string()).unwrap_or_default()),
+                };
+                markdown.push_str(&format!("| {} | {} | {} | {} |\n", escape(&diff.field), escape(old), escape(new), change));
+            }
+            markdown.push('\n');
+        }
+        markdown
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a diff is always valid JSON")
+    }
+
+    /// One `(name, value, inline)` embed field per section that has changes, each kept under
+    /// Discord's limit of 1024 characters.
+    pub fn to_embed_fields(&self) -> Vec<(String, String, bool)> {
+        const LIMIT: usize = 1024;
+        let mut embed_fields = Vec::new();
+        for section in Section::ALL {
+            let fields: Vec<&FieldDiff> = self.section(section).collect();
+            if fields.is_empty() {
+                continue;
+            }
+            let mut value = String::new();
+            for (i, diff) in fields.iter().enumerate() {
+                let line = format!("**{}**: {}\n", diff.field, diff.change);
+                let more = format!("…and {} more", fields.len() - i);
+                if value.chars().count() + line.chars().count() + more.chars().count() > LIMIT {
+                    value.push_str(&more);
+                    break;
+                }
+                value.push_str(&line);
+            }
+            embed_fields.push((format!("{} ({})", section, fields.len()), value, false));
+        }
+        embed_fields
+    }
+}
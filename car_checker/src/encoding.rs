@@ -0,0 +1,68 @@
+//! Decoding of exported CSV files.
+//!
+//! Automation writes its exports as UTF-16LE without a byte order mark, but an
+//! export that went through a text editor may come back as UTF-16BE or UTF-8,
+//! with or without a BOM. The encoding is taken from the BOM when there is one,
+//! and otherwise guessed from where the zero bytes are: CSV headers are ASCII,
+//! so UTF-16 text has a zero in every other byte and UTF-8 text has none.
+
+use anyhow::{bail, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+impl Encoding {
+    /// Detects the encoding of `bytes`, returning it along with the length of its BOM.
+    pub fn detect(bytes: &[u8]) -> (Self, usize) {
+        match bytes {
+            [0xEF, 0xBB, 0xBF, ..] => return (Encoding::Utf8, 3),
+            [0xFF, 0xFE, ..] => return (Encoding::Utf16Le, 2),
+            [0xFE, 0xFF, ..] => return (Encoding::Utf16Be, 2),
+            _ => {},
+        }
+
+        // Only look at the start, the header row is plenty to go on
+        let sample = &bytes[..bytes.len().min(512)];
+        let even_zeros = sample.iter().step_by(2).filter(|b| **b == 0).count();
+        let odd_zeros = sample.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+        if odd_zeros > even_zeros {
+            (Encoding::Utf16Le, 0)
+        } else if even_zeros > odd_zeros {
+            (Encoding::Utf16Be, 0)
+        } else {
+            (Encoding::Utf8, 0)
+        }
+    }
+}
+
+/// Decodes an export into a string, whatever its encoding. The BOM is stripped.
+pub fn decode(bytes: &[u8]) -> Result<String> {
+    let (encoding, bom) = Encoding::detect(bytes);
+    let bytes = &bytes[bom..];
+    match encoding {
+        Encoding::Utf8 => match std::str::from_utf8(bytes) {
+            Ok(text) => Ok(String::from(text)),
+            Err(e) => bail!("File is not valid UTF-8 or UTF-16: {}", e),
+        },
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            if !bytes.len().is_multiple_of(2) {
+                bail!("File looks like UTF-16 but has an odd number of bytes ({}), it's probably cut off", bytes.len());
+            }
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|pair| match encoding {
+                    Encoding::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+                    _ => u16::from_le_bytes([pair[0], pair[1]]),
+                })
+                .collect();
+            match String::from_utf16(&units) {
+                Ok(text) => Ok(text),
+                Err(e) => bail!("File is not valid {:?}: {}", encoding, e),
+            }
+        },
+    }
+}
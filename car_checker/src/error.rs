@@ -0,0 +1,93 @@
+use std::fmt;
+
+/// A column that couldn't be read from a car export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckError {
+    /// The export doesn't have the column at all.
+    Missing { field: String },
+    /// The column is there, but its value isn't what the rule expects (e.g. text instead of a number).
+    Malformed { field: String, value: String, reason: String },
+}
+
+impl CheckError {
+    pub fn field(&self) -> &str {
+        match self {
+            CheckError::Missing { field } | CheckError::Malformed { field, .. } => field,
+        }
+    }
+}
+
+impl fmt::Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckError::Missing { field } => write!(f, "Column `{}` is missing from the export!", field),
+            CheckError::Malformed { field, value, reason } => write!(f, "Column `{}` has an unreadable value `{}` ({})", field, value, reason),
+        }
+    }
+}
+
+impl std::error::Error for CheckError {}
+
+/// A car that couldn't be picked out of an export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportError {
+    /// The export has a header but no cars in it.
+    Empty,
+    /// The export has more than one trim and none was picked.
+    Ambiguous { trims: Vec<String> },
+    /// The picked trim isn't in the export.
+    NoSuchTrim { trim: String, trims: Vec<String> },
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Empty => write!(f, "The export doesn't contain any cars!"),
+            ExportError::Ambiguous { trims } => write!(f, "The export contains {} trims, pick one of: {}", trims.len(), trims.join(", ")),
+            ExportError::NoSuchTrim { trim, trims } => write!(f, "There's no trim `{}` in the export, pick one of: {}", trim, trims.join(", ")),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+/// A submitted zip that couldn't be read safely.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveError {
+    /// The file name isn't a plain `something.zip`.
+    BadFileName { name: String },
+    /// The zip itself, or what it unpacks to, is bigger than allowed.
+    TooLarge { size: u64, limit: u64 },
+    TooManyEntries { entries: usize, limit: usize },
+    /// An entry that would end up outside of the archive when extracted, like `../../token.txt`.
+    UnsafePath { path: String },
+    /// There's no CSV file in the zip.
+    NoCsv,
+    /// There are several CSV files in the zip and none was picked.
+    SeveralCsvs { paths: Vec<String> },
+    /// The picked CSV isn't in the zip.
+    NoSuchCsv { choice: String, paths: Vec<String> },
+    /// The zip is damaged or uses a format that isn't supported.
+    Unreadable { reason: String },
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveError::BadFileName { name } => write!(f, "`{}` isn't a valid zip file name!", name),
+            ArchiveError::TooLarge { size, limit } => write!(f, "The zip is too large ({} KB, the limit is {} KB)!", size / 1024, limit / 1024),
+            ArchiveError::TooManyEntries { entries, limit } => write!(f, "The zip contains {} files, the limit is {}!", entries, limit),
+            ArchiveError::UnsafePath { path } => write!(f, "The zip contains a file with an unsafe path: `{}`", path),
+            ArchiveError::NoCsv => write!(f, "Failed to find a CSV file in the zip!"),
+            ArchiveError::SeveralCsvs { paths } => write!(f, "The zip contains {} CSV files: {}", paths.len(), numbered(paths)),
+            ArchiveError::NoSuchCsv { choice, paths } => write!(f, "There's no CSV `{}` in the zip, pick one of: {}", choice, numbered(paths)),
+            ArchiveError::Unreadable { reason } => write!(f, "The zip couldn't be read: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+fn numbered(paths: &[String]) -> String {
+    paths.iter().enumerate().map(|(i, path)| format!("{}. `{}`", i + 1, path)).collect::<Vec<_>>().join(", ")
+}
@@ -2,42 +2,137 @@ use std::collections::HashMap;
 
 use anyhow::Result;
 
+pub mod archive;
+pub mod car;
+pub mod diff;
+pub mod encoding;
+pub mod error;
 pub mod regulations;
+pub mod report;
+pub mod review;
 
+pub use error::{ArchiveError, CheckError, ExportError};
+
+/// Loads an export from disk. Despite the name, UTF-16BE and UTF-8 files are accepted too.
+/// Fails if the export contains more than one trim, use [`Export`] to pick one.
 pub fn from_utf16_file(path: &str) -> Result<CarData> {
-    let bytes = std::fs::read(path)?;
-    let (_prefix, utf16, _suffix) = unsafe { bytes.align_to::<u16>() };
-    let utf8 = String::from_utf16(&utf16)?;
+    Ok(Export::from_file(path)?.single()?.clone())
+}
 
-    from_utf8_string(utf8)
+/// Loads an export from its raw bytes, detecting the encoding.
+/// Fails if the export contains more than one trim, use [`Export`] to pick one.
+pub fn from_bytes(bytes: &[u8]) -> Result<CarData> {
+    Ok(Export::from_bytes(bytes)?.single()?.clone())
 }
 
+/// Fails if the export contains more than one trim, use [`Export`] to pick one.
 pub fn from_utf8_string(utf8: String) -> Result<CarData> {
-    let mut rdr = csv::Reader::from_reader(utf8.as_bytes());
-    let iter = rdr.deserialize();
-    let mut data: HashMap<String, String> = HashMap::new();
-    if let Some(record) = iter.last() {
-        data = record?;
+    Ok(Export::parse(&utf8)?.single()?.clone())
+}
+
+/// Picks a car out of an export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trim<'a> {
+    /// By its `Trim Name` column.
+    Name(&'a str),
+    /// By its row, starting at 0.
+    Index(usize),
+}
+
+/// Every car in an export. Automation writes one row per exported trim.
+#[derive(Debug, Clone)]
+pub struct Export {
+    rows: Vec<CarData>,
+}
+
+impl Export {
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        let bytes = std::fs::read(path)?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::parse(&encoding::decode(bytes)?)
+    }
+
+    pub fn parse(utf8: &str) -> Result<Self> {
+        let mut rdr = csv::Reader::from_reader(utf8.as_bytes());
+        let mut rows = Vec::new();
+        for record in rdr.deserialize() {
+            let data: HashMap<String, String> = record?;
+            rows.push(CarData(data));
+        }
+        Ok(Self { rows })
+    }
+
+    pub fn rows(&self) -> &[CarData] {
+        &self.rows
+    }
+
+    /// A readable name for every row, for asking which one was meant.
+    pub fn trims(&self) -> Vec<String> {
+        self.rows.iter().enumerate().map(|(i, car_data)| format!("{}. {}", i + 1, car_data.label())).collect()
+    }
+
+    /// The only car in the export, or an error if there's more than one.
+    pub fn single(&self) -> Result<&CarData, ExportError> {
+        match self.rows.as_slice() {
+            [] => Err(ExportError::Empty),
+            [car_data] => Ok(car_data),
+            _ => Err(ExportError::Ambiguous { trims: self.trims() }),
+        }
+    }
+
+    /// Picks a car by trim name or row. A name shared by several rows is ambiguous.
+    pub fn select(&self, trim: Trim) -> Result<&CarData, ExportError> {
+        let found: Vec<usize> = match trim {
+            Trim::Name(name) => (0..self.rows.len()).filter(|i| self.rows[*i].get("Trim Name").is_ok_and(|trim| trim.trim().eq_ignore_ascii_case(name.trim()))).collect(),
+            Trim::Index(index) => (index < self.rows.len()).then_some(index).into_iter().collect(),
+        };
+        match found.as_slice() {
+            [index] => Ok(&self.rows[*index]),
+            [] if self.rows.is_empty() => Err(ExportError::Empty),
+            [] => Err(ExportError::NoSuchTrim {
+                trim: match trim {
+                    Trim::Name(name) => String::from(name),
+                    Trim::Index(index) => format!("#{}", index + 1),
+                },
+                trims: self.trims(),
+            }),
+            _ => {
+                let trims = self.trims();
+                Err(ExportError::Ambiguous { trims: found.iter().map(|i| trims[*i].clone()).collect() })
+            },
+        }
     }
-    Ok(CarData(data))
 }
 
+#[derive(Debug, Clone)]
 pub struct CarData(HashMap<String, String>);
 
+impl From<HashMap<String, String>> for CarData {
+    fn from(fields: HashMap<String, String>) -> Self {
+        Self(fields)
+    }
+}
+
 impl CarData {
-    pub fn get<S: Into<String>>(&self, key: S) -> Result<String, String> {
-        self.0.get(&key.into()).map(|v| v.clone()).ok_or(String::from("Expected value not found!"))
+    pub fn get<S: Into<String>>(&self, key: S) -> Result<String, CheckError> {
+        let field = key.into();
+        self.0.get(&field).cloned().ok_or(CheckError::Missing { field })
     }
 
-    pub fn get_f32<S: Into<String>>(&self, key: S) -> Result<f32, String> {
-        self.get(key).map(|v| v.parse::<f32>())?.map_err(|e| e.to_string())
+    pub fn get_f32<S: Into<String>>(&self, key: S) -> Result<f32, CheckError> {
+        let field = key.into();
+        let value = self.get(field.as_str())?;
+        value.parse::<f32>().map_err(|e| CheckError::Malformed { field, reason: e.to_string(), value })
     }
 
-    pub fn get_usize<S: Into<String>>(&self, key: S) -> Result<usize, String> {
+    pub fn get_usize<S: Into<String>>(&self, key: S) -> Result<usize, CheckError> {
         self.get_f32(key).map(|v| v as usize)
     }
 
-    pub fn get_cost(&self) -> Result<usize, String> {
+    pub fn get_cost(&self) -> Result<usize, CheckError> {
         // Dollar Value =   (Car + Engine Material Costs) +
         //                  25 * (Car + Engine Production Units) +
         //                  (Car Engineering Time * Car Production Units) +
@@ -59,13 +154,31 @@ impl CarData {
         Ok(total_cost)
     }
 
-    pub fn contains_part(&self, part_name: &str) -> bool {
-        for (_key, value) in &self.0 {
-            if value == part_name || value.contains(part_name) {
-                return true;
-            }
+    /// The model, trim and variant names, e.g. "Dysoon Racer (V12)".
+    pub fn label(&self) -> String {
+        let name = |field: &str| self.get(field).unwrap_or_default();
+        let mut label = format!("{} {}", name("Model Name"), name("Trim Name")).trim().to_string();
+        let variant = name("Variant Name");
+        if !variant.is_empty() {
+            label = format!("{} ({})", label, variant);
         }
-        false
+        if label.is_empty() { String::from("unnamed trim") } else { label }
+    }
+
+    pub fn contains_part(&self, part_name: &str) -> bool {
+        self.find_part(part_name).is_some()
+    }
+
+    /// Finds the column (and its value) that uses the given part, if any.
+    pub fn find_part(&self, part_name: &str) -> Option<(&String, &String)> {
+        self.0.iter().filter(|(_key, value)| value.contains(part_name)).min()
+    }
+
+    /// Every column whose name contains all of the given substrings, sorted by name.
+    pub fn keys_containing(&self, parts: &[&str]) -> Vec<String> {
+        let mut keys: Vec<String> = self.0.keys().filter(|key| parts.iter().all(|part| key.contains(part))).cloned().collect();
+        keys.sort();
+        keys
     }
 }
 
@@ -1,10 +1,22 @@
-// Testing file
+// Checks a single export against a rule file, e.g.
+// car_checker "My Car.csv" regulations/mcs_s1_group_c.toml
 
 use car_checker::regulations::Regulations;
+use car_checker::regulations::rules::RuleSet;
 
 fn main() {
-    let car_data = car_checker::from_utf16_file("bepis Dysoon Group C Mid V12.csv").expect("Failed to read!");
-    // println!("car data: {:#?}", car_data);
-    let result = car_checker::regulations::mcs_s1_group_c::MCS_S1_Group_C::default().check(car_data);
-    println!("result: {:#?}", result);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() != 2 {
+        eprintln!("Usage: car_checker <export.csv> <rules.toml>");
+        std::process::exit(1);
+    }
+
+    let car_data = car_checker::from_utf16_file(&args[0]).expect("Failed to read!");
+    let regulations = RuleSet::from_file(&args[1]).expect("Failed to load regulations!");
+    let report = regulations.check(&car_data);
+    if report.is_ok() {
+        println!("{}: passed!", regulations.name);
+    } else {
+        println!("{}: {} issue(s)\n{}", regulations.name, report.violations().len(), report);
+    }
 }
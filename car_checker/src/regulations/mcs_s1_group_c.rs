@@ -1,198 +1,183 @@
+use super::techpool;
+use crate::car::{Aspiration, Car, Differential, Fuel, Gearbox};
+use crate::report::{ensure, Category, Report};
+
+#[allow(non_camel_case_types)]
 #[derive(Default)]
 pub struct MCS_S1_Group_C;
 
 impl super::Regulations for MCS_S1_Group_C {
-    fn check(&self, car_data: crate::CarData) -> Result<(), String> {
-        self.check_tech_pool(&car_data)?;
-        self.check_other(&car_data)?;
-        self.check_stats(&car_data)?;
-        self.check_banned_parts(&car_data)?;
-        self.check_engine(&car_data)?;
-        Ok(())
+    fn check(&self, car_data: &crate::CarData) -> Report {
+        let car = Car::from(car_data);
+        let mut report = Report::new();
+        self.check_tech_pool(&car, &mut report);
+        self.check_other(&car, &mut report);
+        self.check_stats(&car, &mut report);
+        self.check_banned_parts(&car, &mut report);
+        self.check_engine(&car, &mut report);
+        report
     }
 }
 
 impl MCS_S1_Group_C {
-    fn check_tech_pool(&self, car_data: &crate::CarData) -> Result<(), String> {
-        if !car_data.get_usize("Trim Interior Techpool")? == 5 {
-            return Err(String::from("Trim interior techpool must be 5!"));
-        }
-        if !car_data.get_usize("Variant Top End Techpool")? == 5 {
-            return Err(String::from("Variant top end techpool must be 5!"));
-        }
-        if !car_data.get_usize("Variant Bottom End Techpool")? == 5 {
-            return Err(String::from("Variant bottom end techpool must be 5!"));
-        }
-        if !car_data.get_usize("Family Top End Techpool")? == 5 {
-            return Err(String::from("Family top end techpool must be 5!"));
-        }
-        if !car_data.get_usize("Variant Exhaust Techpool")? == 5 {
-            return Err(String::from("Variant exhaust techpool must be 5!"));
-        }
-        if !car_data.get_usize("Family Techpool")? == 5 {
-            return Err(String::from("Family techpool must be 5!"));
-        }
-        if !car_data.get_usize("Model Chassis Techpool")? == 7 {
-            return Err(String::from("Model chassis techpool must be 7!"));
-        }
-        if !car_data.get_usize("Trim Assist Techpool")? == 5 {
-            return Err(String::from("Trim assist techpool must be 5!"));
-        }
-        if !car_data.get_usize("Trim Body Techpool")? == 6 {
-            return Err(String::from("Trim body techpool must be 6!"));
-        }
-        if !car_data.get_usize("Trim Chassis Techpool")? == 7 {
-            return Err(String::from("Trim chassis techpool must be 7!"));
-        }
-        if !car_data.get_usize("Trim Brake Techpool")? == 5 {
-            return Err(String::from("Trim brake techpool must be 5!"));
-        }
-        if !car_data.get_usize("Variant Fuel System Techpool")? == 5 {
-            return Err(String::from("Variant fuel system techpool must be 5!"));
-        }
-        if !car_data.get_usize("Model Body Techpool")? == 6 {
-            return Err(String::from("Model body techpool must be 6!"));
-        }
-        if !car_data.get_usize("Trim Tyre Techpool")? == 7 {
-            return Err(String::from("Trim Tyre techpool must be 7!"));
-        }
-        if !car_data.get_usize("Variant Aspiration Techpool")? == 5 {
-            return Err(String::from("Variant aspiration techpool must be 5!"));
-        }
-        if !car_data.get_usize("Trim Drivetrain Techpool")? == 6 {
-            return Err(String::from("Trim drivetrain techpool must be 6!"));
-        }
-        if !car_data.get_usize("Trim Fixture Techpool")? == 5 {
-            return Err(String::from("Trim fixture techpool must be 5! How the fuck did you manage this?"));
-        }
-        if !car_data.get_usize("Trim Suspension Techpool")? == 5 {
-            return Err(String::from("Trim suspension techpool must be 5!"));
-        }
-        if !car_data.get_usize("Variant Family Techpool")? == 5 {
-            return Err(String::from("Variant family techpool must be 5!"));
-        }
-        if !car_data.get_usize("Trim Aerodynamics Techpool")? == 8 {
-            return Err(String::from("Trim aerodynamics techpool must be 8!"));
-        }
-        if !car_data.get_usize("Trim Safety Techpool")? == 5 {
-            return Err(String::from("Trim safety techpool must be 5!"));
-        }
-        Ok(())
+    fn check_tech_pool(&self, car: &Car, report: &mut Report) {
+        let techpools = techpool::table(&[
+            ("Trim Interior Techpool", 5),
+            ("Variant Top End Techpool", 5),
+            ("Variant Bottom End Techpool", 5),
+            ("Family Top End Techpool", 5),
+            ("Variant Exhaust Techpool", 5),
+            ("Family Techpool", 5),
+            ("Model Chassis Techpool", 7),
+            ("Trim Assist Techpool", 5),
+            ("Trim Body Techpool", 6),
+            ("Trim Chassis Techpool", 7),
+            ("Trim Brake Techpool", 5),
+            ("Variant Fuel System Techpool", 5),
+            ("Model Body Techpool", 6),
+            ("Trim Tyre Techpool", 7),
+            ("Variant Aspiration Techpool", 5),
+            ("Trim Drivetrain Techpool", 6),
+            ("Trim Fixture Techpool", 5),
+            ("Trim Suspension Techpool", 5),
+            ("Variant Family Techpool", 5),
+            ("Trim Aerodynamics Techpool", 8),
+            ("Trim Safety Techpool", 5),
+        ]);
+        techpool::check_tech_pool(&car.techpools, &techpools, None, report);
     }
 
-    fn check_other(&self, car_data: &crate::CarData) -> Result<(), String> {
-        if !car_data.get("Body Name")?.starts_with("LMP") {
-            return Err(String::from("Incorrect body!"));
-        }
-        if car_data.get_usize("Variant Year")? != 1995 {
-            return Err(String::from("Engine year must be 1995!"));
-        }
-        if car_data.get_usize("Engine Family Year")? != 1995 {
-            return Err(String::from("Engine year must be 1995!"));
-        }
-        if car_data.get_usize("Trim Year")? != 1995 {
-            return Err(String::from("Body year must be 1995!"));
-        }
-        if car_data.get_usize("Model Year")? != 1995 {
-            return Err(String::from("Body year must be 1995!"));
+    fn check_other(&self, car: &Car, report: &mut Report) {
+        report.check("body.name", Category::Body, || {
+            let body = car.body.name.get()?;
+            ensure(body.starts_with("LMP"), &car.body.name.name, &body, "starts with LMP", "Incorrect body!")
+        });
+        for (rule, column, message) in [("year.engine", &car.engine.variant_year, "Engine year must be 1995!"), ("year.engine", &car.engine.family_year, "Engine year must be 1995!"), ("year.body", &car.body.trim_year, "Body year must be 1995!"), ("year.body", &car.body.model_year, "Body year must be 1995!")] {
+            report.check(rule, Category::Year, || {
+                let year = column.get()?;
+                ensure(year == 1995, &column.name, year, "= 1995", message)
+            });
         }
 
         // Check seats
-        if car_data.get_usize("Small 1st Row Seats")? > 0 || car_data.get_usize("Small 2nd Row Seats")? > 0 || car_data.get_usize("Small 3rd Row Seats")? > 0 {
-            return Err(String::from("Must not have small seats!"));
+        for column in &car.body.small_seats {
+            report.check("body.small_seats", Category::Body, || {
+                let seats = column.get()?;
+                ensure(seats == 0, &column.name, seats, "= 0", "Must not have small seats!")
+            });
         }
-
-        Ok(())
     }
 
-    fn check_stats(&self, car_data: &crate::CarData) -> Result<(), String> {
-        if car_data.get_f32("Safety Rating")?.round() < 50.0 {
-            return Err(String::from("Safety rating must be at least 50.0!"));
-        }
-        if car_data.get_f32("Trim Economy")?.round() > 23.5 {
-            return Err(String::from("Fuel economy must be 23.5 liters / 100km or better!"));
-        }
-        // if car_data.get_cost()? > 100_000 {
-        //     return Err(String::from("Total cost must be under 100.000$!"));
-        // }
-
-        if car_data.get_f32("Rear Downforce")?.floor() > 850.0 {
-            return Err(String::from("Too much rear downforce!"));
-        }
-        if car_data.get_f32("Front Downforce")?.floor() > 850.0 {
-            return Err(String::from("Too much front downforce!"));
-        }
+    fn check_stats(&self, car: &Car, report: &mut Report) {
+        let stats = &car.stats;
+        report.check("stats.safety", Category::Stats, || {
+            let safety = stats.safety.get()?;
+            ensure(safety.round() >= 50.0, &stats.safety.name, safety, ">= 50", "Safety rating must be at least 50.0!")
+        });
+        report.check("stats.economy", Category::Stats, || {
+            let economy = stats.economy.get()?;
+            ensure(economy.round() <= 23.5, &stats.economy.name, economy, "<= 23.5", "Fuel economy must be 23.5 liters / 100km or better!")
+        });
+        // report.check("stats.cost", Category::Stats, || {
+        //     let cost = car_data.get_cost()?;
+        //     ensure(cost <= 100_000, "Total Cost", cost, "<= 100000", "Total cost must be under 100.000$!")
+        // });
 
-        Ok(())
+        report.check("stats.rear_downforce", Category::Stats, || {
+            let downforce = stats.rear_downforce.get()?;
+            ensure(downforce.floor() <= 850.0, &stats.rear_downforce.name, downforce, "<= 850", "Too much rear downforce!")
+        });
+        report.check("stats.front_downforce", Category::Stats, || {
+            let downforce = stats.front_downforce.get()?;
+            ensure(downforce.floor() <= 850.0, &stats.front_downforce.name, downforce, "<= 850", "Too much front downforce!")
+        });
     }
 
-    fn check_banned_parts(&self, car_data: &crate::CarData) -> Result<(), String> {
-        if car_data.get("Active Aero")? != "None" {
-            return Err(String::from("Active aero is not allowed!"));
-        }
-        if car_data.get("Gearbox Type")?.contains("Adv") || car_data.get("Gearbox Type")?.contains("Auto Manual") {
-            return Err(String::from("Only manual and automatic transmissions allowed!"));
-        }
-        if car_data.get("Differential Type")? != "Geared LSD" {
-            return Err(String::from("Geared LSD is required! If you want to tune it, use a racing differential in BeamNG."));
-        }
-        Ok(())
+    fn check_banned_parts(&self, car: &Car, report: &mut Report) {
+        report.check("banned.active_aero", Category::BannedParts, || {
+            let aero = car.body.active_aero.get()?;
+            ensure(aero == "None", &car.body.active_aero.name, &aero, "= None", "Active aero is not allowed!")
+        });
+        report.check("banned.gearbox", Category::BannedParts, || {
+            let gearbox = car.chassis.gearbox.get()?;
+            ensure(!matches!(gearbox, Gearbox::AdvancedAutomatic | Gearbox::AutoManual), &car.chassis.gearbox.name, &gearbox, "without Adv, Auto Manual", "Only manual and automatic transmissions allowed!")
+        });
+        report.check("banned.differential", Category::BannedParts, || {
+            let differential = car.chassis.differential.get()?;
+            ensure(differential == Differential::GearedLsd, &car.chassis.differential.name, &differential, "= Geared LSD", "Geared LSD is required! If you want to tune it, use a racing differential in BeamNG.")
+        });
     }
 
-    fn check_engine(&self, car_data: &crate::CarData) -> Result<(), String> {
-        if car_data.get("Leaded Fuel")? != "TRUE" {
-            return Err(String::from("Not running leaded fuel!"));
-        }
-        if car_data.get_usize("Fuel Octane")? != 110 {
-            return Err(String::from("Running the wrong fuel octane!"));
-        }
+    fn check_engine(&self, car: &Car, report: &mut Report) {
+        let engine = &car.engine;
+        report.check("engine.leaded_fuel", Category::Engine, || {
+            let fuel = engine.fuel.get()?;
+            ensure(fuel == Fuel::Leaded, &engine.fuel.name, fuel, "= TRUE", "Not running leaded fuel!")
+        });
+        report.check("engine.octane", Category::Engine, || {
+            let octane = engine.octane.get()?;
+            ensure(octane == 110, &engine.octane.name, octane, "= 110", "Running the wrong fuel octane!")
+        });
 
         // Check quality sliders
-        let mut min_quality = 0;
-        let mut max_quality = 0;
-        for (key, value) in car_data.iter() {
-            if key.contains("Variant") && key.contains("Quality") {
-                let qual = value.parse::<f32>().map_err(|e| e.to_string())? as isize;
-                min_quality = min_quality.min(qual);
-                max_quality = max_quality.max(qual);
-            }
-        }
-        if min_quality < -5 || max_quality > 5 {
-            return Err(String::from("Engine quality sliders may not be more than +/- 5!"));
+        for column in car.engine_quality() {
+            report.check("engine.quality_sliders", Category::Engine, || {
+                let quality = column.get()? as isize;
+                ensure((-5..=5).contains(&quality), &column.name, quality, "between -5 and 5", "Engine quality sliders may not be more than +/- 5!")
+            });
+        }
+        for column in &engine.mufflers {
+            report.check("engine.mufflers", Category::Engine, || {
+                let muffler = column.get()?;
+                ensure(muffler != "None", &column.name, &muffler, "not None", "Mufflers cannot be None!")
+            });
         }
-        if car_data.get("Muffler 1")? == "None" || car_data.get("Muffler 2")? == "None" {
-            return Err(String::from("Mufflers cannot be None!"));
-        }
-        if car_data.get("Aspiration")? != "None" && !car_data.get("Aspiration")?.contains("Natural")  {
+
+        let turbo = &car.aspiration;
+        let aspiration = match turbo.kind.get() {
+            Ok(aspiration) => aspiration,
+            Err(e) => return report.check("turbo", Category::Engine, || Err(e.into())),
+        };
+        if !matches!(aspiration, Aspiration::None | Aspiration::NaturallyAspirated) {
             // Car has a turbo
-            if car_data.get_f32("Variant Aspiration Quality")? as isize != 0 {
-                return Err(String::from("Turbo quality must be 0!"));
-            }
-            if (car_data.get_f32("Family Displacement")? * 1000.0).floor() > 1600.0 || (car_data.get_f32("Variant Displacement")? * 1000.0).floor() > 1600.0 {
-                return Err(String::from("Displacement cannot be more than 1.6L!"));
-            }
-            if car_data.get_f32("Peak Boost")? > 2.06 {
-                return Err(String::from("Peak boost cannot be more than 2.06 bar!"));
-            }
-            if car_data.get_f32("Compressor Size 1")? > 90.0 {
-                return Err(String::from("Turbo compressor size too big!"));
-            }
-            if car_data.get_f32("Turbine Size 1")? > 75.0 {
-                return Err(String::from("Turbine size too big!"));
-            }
-            if ((car_data.get_f32("Engine Reliability")? * 10.0).round() / 10.0) < 55.0 {
-                return Err(String::from("Engine reliability is too low!"));
+            report.check("engine.turbo.quality", Category::Engine, || {
+                let quality = turbo.quality.get()? as isize;
+                ensure(quality == 0, &turbo.quality.name, quality, "= 0", "Turbo quality must be 0!")
+            });
+            for column in [&engine.family_displacement, &engine.variant_displacement] {
+                report.check("engine.turbo.displacement", Category::Engine, || {
+                    let displacement = column.get()?;
+                    ensure((displacement * 1000.0).floor() <= 1600.0, &column.name, displacement, "<= 1.6", "Displacement cannot be more than 1.6L!")
+                });
             }
+            report.check("engine.turbo.boost", Category::Engine, || {
+                let boost = turbo.peak_boost.get()?;
+                ensure(boost <= 2.06, &turbo.peak_boost.name, boost, "<= 2.06", "Peak boost cannot be more than 2.06 bar!")
+            });
+            report.check("engine.turbo.compressor", Category::Engine, || {
+                let compressor = turbo.compressor_size.get()?;
+                ensure(compressor <= 90.0, &turbo.compressor_size.name, compressor, "<= 90", "Turbo compressor size too big!")
+            });
+            report.check("engine.turbo.turbine", Category::Engine, || {
+                let turbine = turbo.turbine_size.get()?;
+                ensure(turbine <= 75.0, &turbo.turbine_size.name, turbine, "<= 75", "Turbine size too big!")
+            });
+            report.check("engine.turbo.reliability", Category::Engine, || {
+                let reliability = engine.reliability.get()?;
+                ensure((reliability * 10.0).round() / 10.0 >= 55.0, &engine.reliability.name, reliability, ">= 55", "Engine reliability is too low!")
+            });
         } else {
             // Car does not have a turbo
-            if car_data.get_f32("Family Displacement")? > 3.8 || car_data.get_f32("Variant Displacement")? > 3.8 {
-                return Err(String::from("Displacement cannot be more than 3.8L!"));
-            }
-            if ((car_data.get_f32("Engine Reliability")? * 10.0).round() / 10.0) < 40.0 {
-                return Err(String::from("Engine reliability is too low!"));
+            for column in [&engine.family_displacement, &engine.variant_displacement] {
+                report.check("engine.na.displacement", Category::Engine, || {
+                    let displacement = column.get()?;
+                    ensure(displacement <= 3.8, &column.name, displacement, "<= 3.8", "Displacement cannot be more than 3.8L!")
+                });
             }
+            report.check("engine.na.reliability", Category::Engine, || {
+                let reliability = engine.reliability.get()?;
+                ensure((reliability * 10.0).round() / 10.0 >= 40.0, &engine.reliability.name, reliability, ">= 40", "Engine reliability is too low!")
+            });
         }
-
-        Ok(())
     }
 }
@@ -1,178 +1,210 @@
+use super::techpool;
+use crate::car::{Aspiration, Car, Differential, Fuel, Gearbox};
+use crate::report::{ensure, Category, Report};
+
+#[allow(non_camel_case_types)]
 #[derive(Default)]
 pub struct MCS_S1_GT1;
 
 impl super::Regulations for MCS_S1_GT1 {
-    fn check(&self, car_data: crate::CarData) -> Result<(), String> {
-        self.check_tech_pool(&car_data)?;
-        self.check_other(&car_data)?;
-        self.check_stats(&car_data)?;
-        self.check_banned_parts(&car_data)?;
-        self.check_engine(&car_data)?;
-        Ok(())
+    fn check(&self, car_data: &crate::CarData) -> Report {
+        let car = Car::from(car_data);
+        let mut report = Report::new();
+        self.check_tech_pool(&car, &mut report);
+        self.check_other(&car, &mut report);
+        self.check_stats(&car, &mut report);
+        self.check_banned_parts(&car, &mut report);
+        self.check_engine(&car, &mut report);
+        report
     }
 }
 
 impl MCS_S1_GT1 {
-    fn check_tech_pool(&self, car_data: &crate::CarData) -> Result<(), String> {
-        for (key, value) in car_data.iter() {
-            if key.contains("Techpool") {
-                if value.parse::<f32>().map_err(|e| e.to_string())? as isize != 5 {
-                    return Err(String::from("Techpool must be 5!"));
-                }
-            }
-        }
-        Ok(())
+    fn check_tech_pool(&self, car: &Car, report: &mut Report) {
+        let techpools = techpool::table(&[
+            ("Trim Interior Techpool", 5),
+            ("Variant Top End Techpool", 5),
+            ("Variant Bottom End Techpool", 5),
+            ("Family Top End Techpool", 5),
+            ("Variant Exhaust Techpool", 5),
+            ("Family Techpool", 5),
+            ("Model Chassis Techpool", 5),
+            ("Trim Assist Techpool", 5),
+            ("Trim Body Techpool", 5),
+            ("Trim Chassis Techpool", 5),
+            ("Trim Brake Techpool", 5),
+            ("Variant Fuel System Techpool", 5),
+            ("Model Body Techpool", 5),
+            ("Trim Tyre Techpool", 5),
+            ("Variant Aspiration Techpool", 5),
+            ("Trim Drivetrain Techpool", 5),
+            ("Trim Fixture Techpool", 5),
+            ("Trim Suspension Techpool", 5),
+            ("Variant Family Techpool", 5),
+            ("Trim Aerodynamics Techpool", 5),
+            ("Trim Safety Techpool", 5),
+        ]);
+        // Every other techpool has to be 5 as well
+        techpool::check_tech_pool(&car.techpools, &techpools, Some(5), report);
     }
 
-    fn check_other(&self, car_data: &crate::CarData) -> Result<(), String> {
-        if car_data.get("Body Name")?.starts_with("LMP") {
-            return Err(String::from("Incorrect body!"));
-        }
-        if car_data.get_usize("Variant Year")? != 1995 {
-            return Err(String::from("Engine year must be 1995!"));
-        }
-        if car_data.get_usize("Engine Family Year")? != 1995 {
-            return Err(String::from("Engine year must be 1995!"));
-        }
-        if car_data.get_usize("Trim Year")? != 1995 {
-            return Err(String::from("Body year must be 1995!"));
-        }
-        if car_data.get_usize("Model Year")? != 1995 {
-            return Err(String::from("Body year must be 1995!"));
+    fn check_other(&self, car: &Car, report: &mut Report) {
+        report.check("body.name", Category::Body, || {
+            let body = car.body.name.get()?;
+            ensure(!body.starts_with("LMP"), &car.body.name.name, &body, "doesn't start with LMP", "Incorrect body!")
+        });
+        for (rule, column, message) in [("year.engine", &car.engine.variant_year, "Engine year must be 1995!"), ("year.engine", &car.engine.family_year, "Engine year must be 1995!"), ("year.body", &car.body.trim_year, "Body year must be 1995!"), ("year.body", &car.body.model_year, "Body year must be 1995!")] {
+            report.check(rule, Category::Year, || {
+                let year = column.get()?;
+                ensure(year == 1995, &column.name, year, "= 1995", message)
+            });
         }
 
         // Check seats
-        if car_data.get_usize("Small 1st Row Seats")? > 0 || car_data.get_usize("Small 2nd Row Seats")? > 0 || car_data.get_usize("Small 3rd Row Seats")? > 0 {
-            return Err(String::from("Must not have small seats!"));
+        for column in &car.body.small_seats {
+            report.check("body.small_seats", Category::Body, || {
+                let seats = column.get()?;
+                ensure(seats == 0, &column.name, seats, "= 0", "Must not have small seats!")
+            });
         }
 
         // Check quality sliders
-        let mut min_quality = 0;
-        let mut max_quality = 0;
-        for (key, value) in car_data.iter() {
-            if key.contains("Quality") {
-                let qual = value.parse::<f32>().map_err(|e| e.to_string())? as isize;
-                min_quality = min_quality.min(qual);
-                max_quality = max_quality.max(qual);
-            }
-        }
-
-        if min_quality < -10 || max_quality > 10 {
-            return Err(String::from("Quality sliders may not be more than +/- 10!"));
+        for column in car.quality.iter() {
+            report.check("stats.quality", Category::Stats, || {
+                let quality = column.get()? as isize;
+                ensure((-10..=10).contains(&quality), &column.name, quality, "between -10 and 10", "Quality sliders may not be more than +/- 10!")
+            });
+        }
+
+        report.check("banned.chassis_material", Category::BannedParts, || {
+            let cmat = car.chassis.material.get()?;
+            ensure(!(cmat.contains("Glued") || cmat.contains("Carbon")), &car.chassis.material.name, &cmat, "without Glued, Carbon", &format!("Chassis material cannot be {}", cmat))
+        });
+        report.check("banned.panel_material", Category::BannedParts, || {
+            let pmat = car.body.panel_material.get()?;
+            ensure(!pmat.contains("Carbon"), &car.body.panel_material.name, &pmat, "without Carbon", &format!("Panel material cannot be {}", pmat))
+        });
+        if let Some((field, value)) = car.find_part("Pushrod") {
+            report.check("banned.pushrod", Category::BannedParts, || ensure(false, field, value, "without Pushrod", "Pushrod suspension not allowed!"));
         }
-
-        let cmat = car_data.get("Chassis Material")?;
-        if cmat.contains("Glued") || cmat.contains("Carbon") {
-            return Err(String::from(format!("Chassis material cannot be {}", cmat)));
-        }
-
-        let pmat = car_data.get("Panel Material")?;
-        if pmat.contains("Carbon") {
-            return Err(String::from(format!("Panel material cannot be {}", pmat)));
-        }
-
-        if car_data.contains_part("Pushrod") {
-            return Err(String::from(format!("Pushrod suspension not allowed!")));
-        }
-
-        Ok(())
     }
 
-    fn check_stats(&self, car_data: &crate::CarData) -> Result<(), String> {
-        if car_data.get_f32("Safety Rating")?.round() < 55.0 {
-            return Err(String::from("Safety rating must be at least 50.0!"));
-        }
-        if car_data.get_f32("Trim Economy")?.round() > 15.6 {
-            return Err(String::from("Fuel economy must be 15.6 liters / 100km or better!"));
-        }
-
-        if car_data.get_f32("Rear Downforce")?.floor() > 250.0 {
-            return Err(String::from("Too much rear downforce!"));
-        }
-        if car_data.get_f32("Front Downforce")?.floor() > 250.0 {
-            return Err(String::from("Too much front downforce!"));
-        }
-
-        Ok(())
+    fn check_stats(&self, car: &Car, report: &mut Report) {
+        let stats = &car.stats;
+        report.check("stats.safety", Category::Stats, || {
+            let safety = stats.safety.get()?;
+            ensure(safety.round() >= 55.0, &stats.safety.name, safety, ">= 55", "Safety rating must be at least 55.0!")
+        });
+        report.check("stats.economy", Category::Stats, || {
+            let economy = stats.economy.get()?;
+            ensure(economy.round() <= 15.6, &stats.economy.name, economy, "<= 15.6", "Fuel economy must be 15.6 liters / 100km or better!")
+        });
+
+        report.check("stats.rear_downforce", Category::Stats, || {
+            let downforce = stats.rear_downforce.get()?;
+            ensure(downforce.floor() <= 250.0, &stats.rear_downforce.name, downforce, "<= 250", "Too much rear downforce!")
+        });
+        report.check("stats.front_downforce", Category::Stats, || {
+            let downforce = stats.front_downforce.get()?;
+            ensure(downforce.floor() <= 250.0, &stats.front_downforce.name, downforce, "<= 250", "Too much front downforce!")
+        });
     }
 
-    fn check_banned_parts(&self, car_data: &crate::CarData) -> Result<(), String> {
-        if car_data.get("Active Aero")? != "None" {
-            return Err(String::from("Active aero is not allowed!"));
-        }
-        if car_data.get("Gearbox Type")?.contains("Adv") {
-            return Err(String::from("Only manual and automatic transmissions allowed!"));
-        }
-        if car_data.get("Differential Type")? != "Geared LSD" {
-            return Err(String::from("Geared LSD is required! If you want to tune it, use a racing differential in BeamNG."));
-        }
-        Ok(())
+    fn check_banned_parts(&self, car: &Car, report: &mut Report) {
+        report.check("banned.active_aero", Category::BannedParts, || {
+            let aero = car.body.active_aero.get()?;
+            ensure(aero == "None", &car.body.active_aero.name, &aero, "= None", "Active aero is not allowed!")
+        });
+        report.check("banned.gearbox", Category::BannedParts, || {
+            let gearbox = car.chassis.gearbox.get()?;
+            ensure(gearbox != Gearbox::AdvancedAutomatic, &car.chassis.gearbox.name, &gearbox, "without Adv", "Only manual and automatic transmissions allowed!")
+        });
+        report.check("banned.differential", Category::BannedParts, || {
+            let differential = car.chassis.differential.get()?;
+            ensure(differential == Differential::GearedLsd, &car.chassis.differential.name, &differential, "= Geared LSD", "Geared LSD is required! If you want to tune it, use a racing differential in BeamNG.")
+        });
     }
 
-    fn check_engine(&self, car_data: &crate::CarData) -> Result<(), String> {
-        if car_data.get("Leaded Fuel")? == "TRUE" {
-            return Err(String::from("Running leaded fuel!"));
-        }
-        if car_data.get_usize("Fuel Octane")? != 98 {
-            return Err(String::from("Running the wrong fuel octane!"));
-        }
-        if car_data.get("Aspiration")? != "None" {
+    fn check_engine(&self, car: &Car, report: &mut Report) {
+        let engine = &car.engine;
+        report.check("engine.leaded_fuel", Category::Engine, || {
+            let fuel = engine.fuel.get()?;
+            ensure(fuel != Fuel::Leaded, &engine.fuel.name, fuel, "not TRUE", "Running leaded fuel!")
+        });
+        report.check("engine.octane", Category::Engine, || {
+            let octane = engine.octane.get()?;
+            ensure(octane == 98, &engine.octane.name, octane, "= 98", "Running the wrong fuel octane!")
+        });
+
+        let turbo = &car.aspiration;
+        let aspiration = match turbo.kind.get() {
+            Ok(aspiration) => aspiration,
+            Err(e) => return report.check("turbo", Category::Engine, || Err(e.into())),
+        };
+        if aspiration != Aspiration::None {
             // Car has a turbo
-            if car_data.get("Aspiration")?.contains("Twin") || car_data.get("Aspiration")?.contains("Quad") {
-                return Err(String::from("Twin/Quad turbo setups not allowed!"));
-            }
-            if car_data.get_usize("Cylinder Count")? > 8 {
-                return Err(String::from("Can't have more than 8 cylinders!"));
-            }
-            if car_data.get("Intake")? == "Race" {
-                return Err(String::from("Intake manifold can't be race!"));
-            }
-            if car_data.get_f32("Variant Aspiration Quality")? as isize != 0 {
-                return Err(String::from("Turbo quality must be 0!"));
-            }
-            if (car_data.get_f32("Family Displacement")? * 1000.0).floor() > 1700.0 || (car_data.get_f32("Variant Displacement")? * 1000.0).floor() > 1700.0 {
-                return Err(String::from("Displacement cannot be more than 1.7L!"));
-            }
-            if car_data.get_f32("Peak Boost")? > 2.06 {
-                return Err(String::from("Peak boost cannot be more than 2.06 bar!"));
-            }
-            if car_data.get_f32("Compressor Size 1")? > 90.0 {
-                return Err(String::from("Turbo compressor size too big!"));
-            }
-            if car_data.get_f32("Turbine Size 1")? > 75.0 {
-                return Err(String::from("Turbine size too big!"));
-            }
-            if ((car_data.get_f32("Engine Reliability")? * 10.0).round() / 10.0) < 55.0 {
-                return Err(String::from("Engine reliability is too low!"));
+            report.check("engine.turbo.count", Category::Engine, || ensure(!matches!(aspiration, Aspiration::TwinTurbo | Aspiration::QuadTurbo), &turbo.kind.name, &aspiration, "without Twin, Quad", "Twin/Quad turbo setups not allowed!"));
+            report.check("engine.turbo.cylinders", Category::Engine, || {
+                let cylinders = engine.cylinders.get()?;
+                ensure(cylinders <= 8, &engine.cylinders.name, cylinders, "<= 8", "Can't have more than 8 cylinders!")
+            });
+            report.check("engine.turbo.intake", Category::Engine, || {
+                let intake = engine.intake.get()?;
+                ensure(intake != "Race", &engine.intake.name, &intake, "not Race", "Intake manifold can't be race!")
+            });
+            report.check("engine.turbo.quality", Category::Engine, || {
+                let quality = turbo.quality.get()? as isize;
+                ensure(quality == 0, &turbo.quality.name, quality, "= 0", "Turbo quality must be 0!")
+            });
+            for column in [&engine.family_displacement, &engine.variant_displacement] {
+                report.check("engine.turbo.displacement", Category::Engine, || {
+                    let displacement = column.get()?;
+                    ensure((displacement * 1000.0).floor() <= 1700.0, &column.name, displacement, "<= 1.7", "Displacement cannot be more than 1.7L!")
+                });
             }
+            report.check("engine.turbo.boost", Category::Engine, || {
+                let boost = turbo.peak_boost.get()?;
+                ensure(boost <= 2.06, &turbo.peak_boost.name, boost, "<= 2.06", "Peak boost cannot be more than 2.06 bar!")
+            });
+            report.check("engine.turbo.compressor", Category::Engine, || {
+                let compressor = turbo.compressor_size.get()?;
+                ensure(compressor <= 90.0, &turbo.compressor_size.name, compressor, "<= 90", "Turbo compressor size too big!")
+            });
+            report.check("engine.turbo.turbine", Category::Engine, || {
+                let turbine = turbo.turbine_size.get()?;
+                ensure(turbine <= 75.0, &turbo.turbine_size.name, turbine, "<= 75", "Turbine size too big!")
+            });
+            report.check("engine.turbo.reliability", Category::Engine, || {
+                let reliability = engine.reliability.get()?;
+                ensure((reliability * 10.0).round() / 10.0 >= 55.0, &engine.reliability.name, reliability, ">= 55", "Engine reliability is too low!")
+            });
 
             // Check quality sliders
-            let mut min_quality = 0;
-            let mut max_quality = 0;
-            for (key, value) in car_data.iter() {
-                if key.contains("Variant") && key.contains("Quality") {
-                    let qual = value.parse::<f32>().map_err(|e| e.to_string())? as isize;
-                    min_quality = min_quality.min(qual);
-                    max_quality = max_quality.max(qual);
-                }
-            }
-            if min_quality < -5 || max_quality > 5 {
-                return Err(String::from("Engine quality sliders may not be more than +/- 5!"));
+            for column in car.engine_quality() {
+                report.check("engine.turbo.quality_sliders", Category::Engine, || {
+                    let quality = column.get()? as isize;
+                    ensure((-5..=5).contains(&quality), &column.name, quality, "between -5 and 5", "Engine quality sliders may not be more than +/- 5!")
+                });
             }
         } else {
             // Car does not have a turbo
-            if car_data.get_f32("Family Displacement")? > 4.5 || car_data.get_f32("Variant Displacement")? > 4.5 {
-                return Err(String::from("Displacement cannot be more than 3.5L!"));
-            } else if car_data.get_f32("Family Displacement")? > 3.3 || car_data.get_f32("Variant Displacement")? > 3.3 {
-                if car_data.contains_part("Tubular Race") {
-                    return Err(String::from("Not allowed race headers when your engine is bigger than 3.3L!"));
-                }
+            let mut big_engine = false;
+            for column in [&engine.family_displacement, &engine.variant_displacement] {
+                report.check("engine.na.displacement", Category::Engine, || {
+                    let displacement = column.get()?;
+                    if displacement > 3.3 {
+                        big_engine = true;
+                    }
+                    ensure(displacement <= 4.5, &column.name, displacement, "<= 4.5", "Displacement cannot be more than 4.5L!")
+                });
             }
-            if ((car_data.get_f32("Engine Reliability")? * 10.0).round() / 10.0) < 45.0 {
-                return Err(String::from("Engine reliability is too low!"));
+            if let Some((field, value)) = car.find_part("Tubular Race").filter(|_| big_engine) {
+                report.check("engine.na.headers", Category::Engine, || ensure(false, field, value, "without Tubular Race", "Not allowed race headers when your engine is bigger than 3.3L!"));
             }
+            report.check("engine.na.reliability", Category::Engine, || {
+                let reliability = engine.reliability.get()?;
+                ensure((reliability * 10.0).round() / 10.0 >= 45.0, &engine.reliability.name, reliability, ">= 45", "Engine reliability is too low!")
+            });
         }
-
-        Ok(())
     }
 }
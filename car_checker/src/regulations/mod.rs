@@ -1,8 +1,12 @@
 use crate::CarData;
+use crate::report::Report;
 
 pub mod mcs_s1_group_c;
 pub mod mcs_s1_gt1;
+pub mod rules;
+pub mod techpool;
 
 pub trait Regulations {
-    fn check(&self, car_data: CarData) -> core::result::Result<(), String>;
+    /// Checks the car against every rule and reports all violations found.
+    fn check(&self, car_data: &CarData) -> Report;
 }
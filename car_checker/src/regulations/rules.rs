@@ -0,0 +1,457 @@
+//! Regulations loaded at runtime from a TOML rule file.
+//!
+//! A rule file has a `name`, a `[techpool]` table of column -> required value
+//! with an optional `unlisted_techpool` every other techpool column must have,
+//! optional named `[conditions.*]` and a list of `[[rules]]`.
+//! Every rule selects one or more CSV columns and runs either a number check
+//! (`min`, `max`, `equals`) or a text check (`is`, `is_not`, `starts_with`,
+//! `not_starts_with`, `banned`) on each of them. Rules can be limited to cars
+//! matching (`when`) or not matching (`unless`) a condition, which is how the
+//! turbo and naturally aspirated branches are written.
+//!
+//! A `[[frozen]]` entry selects columns the same way but has no check: it lists
+//! components that may not change between rounds, and is evaluated on the diff
+//! between two versions of a car by [`RuleSet::review`].
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+
+use crate::car::Car;
+use crate::diff::{CarDiff, Section};
+use crate::report::{Category, Report, Violation, ViolationKind};
+use crate::review::{Flag, Review};
+use crate::{CarData, CheckError};
+
+/// How a number is rounded before it's compared to its limits.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rounding {
+    #[default]
+    None,
+    Nearest,
+    Floor,
+    Trunc,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRuleSet {
+    name: String,
+    #[serde(default)]
+    techpool: BTreeMap<String, usize>,
+    unlisted_techpool: Option<usize>,
+    #[serde(default)]
+    conditions: HashMap<String, RawCheck>,
+    #[serde(default)]
+    rules: Vec<RawCheck>,
+    #[serde(default)]
+    frozen: Vec<RawCheck>,
+}
+
+/// A rule or condition as written in the file, before validation.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawCheck {
+    id: Option<String>,
+    category: Option<Category>,
+    message: Option<String>,
+    #[serde(default)]
+    when: Vec<String>,
+    #[serde(default)]
+    unless: Vec<String>,
+
+    field: Option<String>,
+    #[serde(default)]
+    fields: Vec<String>,
+    #[serde(default)]
+    fields_containing: Vec<String>,
+    #[serde(default)]
+    all_fields: bool,
+    section: Option<Section>,
+
+    min: Option<f32>,
+    max: Option<f32>,
+    equals: Option<f32>,
+    #[serde(default)]
+    round: Rounding,
+    #[serde(default)]
+    decimals: u32,
+
+    is: Option<String>,
+    is_not: Option<String>,
+    starts_with: Option<String>,
+    not_starts_with: Option<String>,
+    #[serde(default)]
+    banned: Vec<String>,
+}
+
+#[derive(Debug)]
+enum Selector {
+    Fields(Vec<String>),
+    /// Every column whose name contains all of the given substrings.
+    Containing(Vec<String>),
+    /// Every column in the export.
+    All,
+    /// Every column in a section of the designer.
+    Section(Section),
+}
+
+#[derive(Debug)]
+enum Test {
+    Number {
+        min: Option<f32>,
+        max: Option<f32>,
+        equals: Option<f32>,
+        round: Rounding,
+        decimals: u32,
+    },
+    Text {
+        is: Option<String>,
+        is_not: Option<String>,
+        starts_with: Option<String>,
+        not_starts_with: Option<String>,
+        banned: Vec<String>,
+    },
+}
+
+#[derive(Debug)]
+struct Check {
+    selector: Selector,
+    test: Test,
+}
+
+#[derive(Debug)]
+struct Rule {
+    id: String,
+    category: Category,
+    message: String,
+    when: Vec<String>,
+    unless: Vec<String>,
+    check: Check,
+}
+
+/// Components that can't change between rounds.
+#[derive(Debug)]
+struct Frozen {
+    id: String,
+    message: String,
+    when: Vec<String>,
+    unless: Vec<String>,
+    selector: Selector,
+}
+
+/// A series' regulations, loaded from a rule file.
+#[derive(Debug)]
+pub struct RuleSet {
+    pub name: String,
+    techpool: BTreeMap<String, usize>,
+    unlisted_techpool: Option<usize>,
+    conditions: HashMap<String, Check>,
+    rules: Vec<Rule>,
+    frozen: Vec<Frozen>,
+}
+
+impl RuleSet {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path).with_context(|| format!("Failed to read rule file {}", path.display()))?;
+        Self::parse(&source).with_context(|| format!("Invalid rule file {}", path.display()))
+    }
+
+    pub fn parse(source: &str) -> Result<Self> {
+        let raw: RawRuleSet = toml::from_str(source)?;
+
+        let mut conditions = HashMap::new();
+        for (name, raw_check) in raw.conditions {
+            if raw_check.id.is_some() || raw_check.category.is_some() || raw_check.message.is_some() || !raw_check.when.is_empty() || !raw_check.unless.is_empty() {
+                bail!("Condition `{}` can only select fields and test them", name);
+            }
+            let check = Check::from_raw(&raw_check).with_context(|| format!("Invalid condition `{}`", name))?;
+            conditions.insert(name, check);
+        }
+
+        let mut rules = Vec::new();
+        let mut ids = HashSet::new();
+        for raw_rule in raw.rules {
+            let id = raw_rule.id.clone().ok_or_else(|| anyhow!("Every rule needs an `id`"))?;
+            if !ids.insert(id.clone()) {
+                bail!("Duplicate rule id `{}`", id);
+            }
+            for name in raw_rule.when.iter().chain(&raw_rule.unless) {
+                if !conditions.contains_key(name) {
+                    bail!("Rule `{}` refers to unknown condition `{}`", id, name);
+                }
+            }
+            let check = Check::from_raw(&raw_rule).with_context(|| format!("Invalid rule `{}`", id))?;
+            rules.push(Rule {
+                category: raw_rule.category.ok_or_else(|| anyhow!("Rule `{}` needs a `category`", id))?,
+                message: raw_rule.message.ok_or_else(|| anyhow!("Rule `{}` needs a `message`", id))?,
+                when: raw_rule.when,
+                unless: raw_rule.unless,
+                id,
+                check,
+            });
+        }
+
+        let mut frozen = Vec::new();
+        for raw_frozen in raw.frozen {
+            let id = raw_frozen.id.clone().ok_or_else(|| anyhow!("Every frozen entry needs an `id`"))?;
+            if !ids.insert(id.clone()) {
+                bail!("Duplicate rule id `{}`", id);
+            }
+            if raw_frozen.category.is_some() || raw_frozen.has_test() {
+                bail!("Frozen entry `{}` can only select fields", id);
+            }
+            for name in raw_frozen.when.iter().chain(&raw_frozen.unless) {
+                if !conditions.contains_key(name) {
+                    bail!("Frozen entry `{}` refers to unknown condition `{}`", id, name);
+                }
+            }
+            frozen.push(Frozen {
+                selector: Selector::from_raw(&raw_frozen).with_context(|| format!("Invalid frozen entry `{}`", id))?,
+                message: raw_frozen.message.ok_or_else(|| anyhow!("Frozen entry `{}` needs a `message`", id))?,
+                when: raw_frozen.when,
+                unless: raw_frozen.unless,
+                id,
+            });
+        }
+
+        Ok(Self { name: raw.name, techpool: raw.techpool, unlisted_techpool: raw.unlisted_techpool, conditions, rules, frozen })
+    }
+
+    /// Flags every change to a frozen component between two versions of a car. `car_data` is the
+    /// new version, which `when` and `unless` are evaluated on. A condition that can't be
+    /// evaluated counts as met, so nothing slips through unreviewed.
+    pub fn review(&self, diff: &CarDiff, car_data: &CarData) -> Review {
+        let mut review = Review::default();
+        for frozen in &self.frozen {
+            let applies = frozen.when.iter().all(|name| self.conditions[name].holds(car_data).unwrap_or(true))
+                && frozen.unless.iter().all(|name| !self.conditions[name].holds(car_data).unwrap_or(false));
+            if !applies {
+                continue;
+            }
+            for field in diff.fields.iter().filter(|field| frozen.selector.matches(&field.field)) {
+                review.flags.push(Flag {
+                    rule: frozen.id.clone(),
+                    message: frozen.message.replace("{field}", &field.field),
+                    field: field.field.clone(),
+                    change: field.change.clone(),
+                });
+            }
+        }
+        review
+    }
+}
+
+impl RawCheck {
+    fn has_test(&self) -> bool {
+        self.min.is_some() || self.max.is_some() || self.equals.is_some()
+            || self.is.is_some() || self.is_not.is_some() || self.starts_with.is_some() || self.not_starts_with.is_some() || !self.banned.is_empty()
+    }
+}
+
+impl Selector {
+    fn from_raw(raw: &RawCheck) -> Result<Self> {
+        let mut selectors = Vec::new();
+        if let Some(field) = &raw.field {
+            selectors.push(Selector::Fields(vec![field.clone()]));
+        }
+        if !raw.fields.is_empty() {
+            selectors.push(Selector::Fields(raw.fields.clone()));
+        }
+        if !raw.fields_containing.is_empty() {
+            selectors.push(Selector::Containing(raw.fields_containing.clone()));
+        }
+        if raw.all_fields {
+            selectors.push(Selector::All);
+        }
+        if let Some(section) = raw.section {
+            selectors.push(Selector::Section(section));
+        }
+        if selectors.len() != 1 {
+            bail!("Use exactly one of `field`, `fields`, `fields_containing`, `all_fields` or `section`");
+        }
+        Ok(selectors.remove(0))
+    }
+
+    /// True if the selector picks the given column.
+    fn matches(&self, field: &str) -> bool {
+        match self {
+            Selector::Fields(fields) => fields.iter().any(|selected| selected == field),
+            Selector::Containing(parts) => parts.iter().all(|part| field.contains(part.as_str())),
+            Selector::All => true,
+            Selector::Section(section) => Section::of(field) == *section,
+        }
+    }
+}
+
+impl Check {
+    fn from_raw(raw: &RawCheck) -> Result<Self> {
+        let selector = Selector::from_raw(raw)?;
+
+        let is_number = raw.min.is_some() || raw.max.is_some() || raw.equals.is_some();
+        let is_text = raw.is.is_some() || raw.is_not.is_some() || raw.starts_with.is_some() || raw.not_starts_with.is_some() || !raw.banned.is_empty();
+        let test = match (is_number, is_text) {
+            (true, false) => Test::Number {
+                min: raw.min,
+                max: raw.max,
+                equals: raw.equals,
+                round: raw.round,
+                decimals: raw.decimals,
+            },
+            (false, true) => Test::Text {
+                is: raw.is.clone(),
+                is_not: raw.is_not.clone(),
+                starts_with: raw.starts_with.clone(),
+                not_starts_with: raw.not_starts_with.clone(),
+                banned: raw.banned.clone(),
+            },
+            (true, true) => bail!("Number and text checks can't be mixed"),
+            (false, false) => bail!("Nothing to check"),
+        };
+
+        Ok(Self { selector, test })
+    }
+
+    /// The columns this check applies to, in a stable order.
+    fn fields(&self, car_data: &CarData) -> Vec<String> {
+        match &self.selector {
+            Selector::Fields(fields) => fields.clone(),
+            Selector::Containing(parts) => {
+                let parts: Vec<&str> = parts.iter().map(String::as_str).collect();
+                car_data.keys_containing(&parts)
+            },
+            Selector::All => car_data.keys_containing(&[]),
+            Selector::Section(section) => car_data.keys_containing(&[]).into_iter().filter(|field| Section::of(field) == *section).collect(),
+        }
+    }
+
+    /// Tests a single column. `Ok(false)` means the value is outside the rules.
+    fn test(&self, car_data: &CarData, field: &str) -> Result<bool, CheckError> {
+        let value = car_data.get(field)?;
+        Ok(match &self.test {
+            Test::Number { min, max, equals, round, decimals } => {
+                let number = car_data.get_f32(field)?;
+                let number = round_number(number, *round, *decimals);
+                min.is_none_or(|min| number >= min)
+                    && max.is_none_or(|max| number <= max)
+                    && equals.is_none_or(|equals| number == equals)
+            },
+            Test::Text { is, is_not, starts_with, not_starts_with, banned } => {
+                is.as_ref().is_none_or(|is| &value == is)
+                    && is_not.as_ref().is_none_or(|is_not| &value != is_not)
+                    && starts_with.as_ref().is_none_or(|prefix| value.starts_with(prefix.as_str()))
+                    && not_starts_with.as_ref().is_none_or(|prefix| !value.starts_with(prefix.as_str()))
+                    && !banned.iter().any(|part| value.contains(part.as_str()))
+            },
+        })
+    }
+
+    /// What the rule allows, for showing next to the actual value.
+    fn limit(&self) -> String {
+        match &self.test {
+            Test::Number { min, max, equals, .. } => {
+                let mut limits = Vec::new();
+                match (min, max) {
+                    (Some(min), Some(max)) => limits.push(format!("between {} and {}", min, max)),
+                    (Some(min), None) => limits.push(format!(">= {}", min)),
+                    (None, Some(max)) => limits.push(format!("<= {}", max)),
+                    (None, None) => {},
+                }
+                if let Some(equals) = equals {
+                    limits.push(format!("= {}", equals));
+                }
+                limits.join(" and ")
+            },
+            Test::Text { is, is_not, starts_with, not_starts_with, banned } => {
+                let mut limits = Vec::new();
+                if let Some(is) = is {
+                    limits.push(format!("= {}", is));
+                }
+                if let Some(is_not) = is_not {
+                    limits.push(format!("not {}", is_not));
+                }
+                if let Some(prefix) = starts_with {
+                    limits.push(format!("starts with {}", prefix));
+                }
+                if let Some(prefix) = not_starts_with {
+                    limits.push(format!("doesn't start with {}", prefix));
+                }
+                if !banned.is_empty() {
+                    limits.push(format!("without {}", banned.join(", ")));
+                }
+                limits.join(" and ")
+            },
+        }
+    }
+
+    /// True if every selected column passes.
+    fn holds(&self, car_data: &CarData) -> Result<bool, CheckError> {
+        for field in self.fields(car_data) {
+            if !self.test(car_data, &field)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn round_number(number: f32, round: Rounding, decimals: u32) -> f32 {
+    let scale = 10f32.powi(decimals as i32);
+    let scaled = number * scale;
+    let rounded = match round {
+        Rounding::None => return number,
+        Rounding::Nearest => scaled.round(),
+        Rounding::Floor => scaled.floor(),
+        Rounding::Trunc => scaled.trunc(),
+    };
+    if decimals == 0 { rounded } else { rounded / scale }
+}
+
+impl super::Regulations for RuleSet {
+    fn check(&self, car_data: &CarData) -> Report {
+        let mut report = Report::new();
+        super::techpool::check_tech_pool(&Car::from(car_data).techpools, &self.techpool, self.unlisted_techpool, &mut report);
+
+        let mut conditions = HashMap::new();
+        let mut broken_conditions = HashSet::new();
+
+        'rules: for rule in &self.rules {
+            for (name, expected) in rule.when.iter().map(|name| (name, true)).chain(rule.unless.iter().map(|name| (name, false))) {
+                let holds = conditions.entry(name.as_str()).or_insert_with(|| self.conditions[name].holds(car_data));
+                match holds {
+                    Ok(holds) if *holds == expected => {},
+                    Ok(_) => continue 'rules,
+                    Err(e) => {
+                        // Only report a condition that can't be evaluated once, under the condition's name
+                        if broken_conditions.insert(name.as_str()) {
+                            report.push(Violation { rule: name.clone(), category: rule.category, kind: ViolationKind::Data(e.clone()) });
+                        }
+                        continue 'rules;
+                    },
+                }
+            }
+
+            for field in rule.check.fields(car_data) {
+                report.check(&rule.id, rule.category, || {
+                    if rule.check.test(car_data, &field)? {
+                        Ok(())
+                    } else {
+                        let value = car_data.get(&field)?;
+                        Err(ViolationKind::Breach {
+                            message: rule.message.replace("{field}", &field).replace("{value}", &value),
+                            limit: rule.check.limit(),
+                            actual: value,
+                            field,
+                        })
+                    }
+                });
+            }
+        }
+
+        report
+    }
+}
@@ -0,0 +1,28 @@
+use std::collections::BTreeMap;
+
+use crate::car::Columns;
+use crate::report::{ensure, Category, Report};
+
+/// Checks every listed techpool column against the value the series requires. Columns the table
+/// doesn't list, like ones added by a game update, have to be `unlisted` if that's given.
+pub fn check_tech_pool(techpools: &Columns<usize>, required: &BTreeMap<String, usize>, unlisted: Option<usize>, report: &mut Report) {
+    for (field, required) in required {
+        report.check("techpool", Category::Techpool, || {
+            let techpool = techpools.get(field)?;
+            ensure(techpool == *required, field, techpool, &format!("= {}", required), &format!("{} must be {}!", field, required))
+        });
+    }
+    if let Some(unlisted) = unlisted {
+        for column in techpools.iter().filter(|column| !required.contains_key(&column.name)) {
+            report.check("techpool", Category::Techpool, || {
+                let techpool = column.get()?;
+                ensure(techpool == unlisted, &column.name, techpool, &format!("= {}", unlisted), &format!("{} must be {}!", column.name, unlisted))
+            });
+        }
+    }
+}
+
+/// Builds the column -> required value map from a list of pairs.
+pub fn table(techpools: &[(&str, usize)]) -> BTreeMap<String, usize> {
+    techpools.iter().map(|(field, required)| (String::from(*field), *required)).collect()
+}
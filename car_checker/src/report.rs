@@ -0,0 +1,138 @@
+use std::fmt;
+
+use serde::Deserialize;
+
+use crate::CheckError;
+
+/// The part of the regulations a violation falls under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    Techpool,
+    Year,
+    Body,
+    Stats,
+    BannedParts,
+    Engine,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Category::Techpool => "Techpool",
+            Category::Year => "Year",
+            Category::Body => "Body",
+            Category::Stats => "Stats",
+            Category::BannedParts => "Banned parts",
+            Category::Engine => "Engine",
+        };
+        f.write_str(name)
+    }
+}
+
+/// What went wrong with a single rule.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViolationKind {
+    /// The car breaks the rule.
+    Breach {
+        field: String,
+        actual: String,
+        limit: String,
+        message: String,
+    },
+    /// The rule couldn't be checked, because its column is missing or malformed.
+    Data(CheckError),
+}
+
+impl From<CheckError> for ViolationKind {
+    fn from(error: CheckError) -> Self {
+        ViolationKind::Data(error)
+    }
+}
+
+/// A single rule the car didn't pass.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    pub rule: String,
+    pub category: Category,
+    pub kind: ViolationKind,
+}
+
+impl Violation {
+    /// The CSV column the violation is about.
+    pub fn field(&self) -> &str {
+        match &self.kind {
+            ViolationKind::Breach { field, .. } => field,
+            ViolationKind::Data(error) => error.field(),
+        }
+    }
+
+    /// True if the car breaks the rule, false if the export itself is at fault.
+    pub fn is_breach(&self) -> bool {
+        matches!(self.kind, ViolationKind::Breach { .. })
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ViolationKind::Breach { field, actual, limit, message } => write!(f, "{} ({} is {}, allowed: {})", message, field, actual, limit),
+            ViolationKind::Data(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// Every regulation violation found while checking a car.
+#[derive(Debug, Default)]
+pub struct Report {
+    violations: Vec<Violation>,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs a single rule, recording its violation (if any) instead of bailing out.
+    pub fn check<F: FnOnce() -> Result<(), ViolationKind>>(&mut self, rule: &str, category: Category, check: F) {
+        if let Err(kind) = check() {
+            self.push(Violation { rule: String::from(rule), category, kind });
+        }
+    }
+
+    pub fn push(&mut self, violation: Violation) {
+        self.violations.push(violation);
+    }
+
+    /// True if the car passed every rule.
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+}
+
+/// Turns a rule's condition into the `Result` expected by [`Report::check`].
+pub fn ensure<T: ToString>(condition: bool, field: &str, actual: T, limit: &str, message: &str) -> Result<(), ViolationKind> {
+    if condition {
+        Ok(())
+    } else {
+        Err(ViolationKind::Breach {
+            field: String::from(field),
+            actual: actual.to_string(),
+            limit: String::from(limit),
+            message: String::from(message),
+        })
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for violation in &self.violations {
+            writeln!(f, "[{}] {}", violation.category, violation)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+//! Reviewing the changes between two versions of a car.
+//!
+//! Unlike a [`Report`](crate::report::Report), a review doesn't reject anything:
+//! it flags changes to components the regulations freeze for the season, for the
+//! EMs to look at before the new version is accepted.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::diff::Change;
+
+/// A change to a frozen component.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Flag {
+    pub rule: String,
+    pub message: String,
+    pub field: String,
+    pub change: Change,
+}
+
+impl fmt::Display for Flag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}: {} ({})", self.rule, self.message, self.field, self.change)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Review {
+    pub flags: Vec<Flag>,
+}
+
+impl Review {
+    pub fn is_clean(&self) -> bool {
+        self.flags.is_empty()
+    }
+
+    /// A list of the flagged changes, to go above the diff itself.
+    pub fn to_markdown(&self) -> String {
+        if self.is_clean() {
+            return String::from("No changes to frozen components.\n");
+        }
+        let mut markdown = String::from("## Flagged changes\n\n");
+        for flag in &self.flags {
+            markdown.push_str(&format!("- **{}** `{}`: {} ({})\n", flag.message, flag.field, flag.change, flag.rule));
+        }
+        markdown.push('\n');
+        markdown
+    }
+}
+
+impl fmt::Display for Review {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for flag in &self.flags {
+            writeln!(f, "{}", flag)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use std::io::{Cursor, Write};
+
+use car_checker::archive::{self, Limits};
+use car_checker::ArchiveError;
+use zip::write::FileOptions;
+
+/// Zips up the given files in memory.
+fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, contents) in files {
+        writer.start_file(*name, FileOptions::default()).unwrap();
+        writer.write_all(contents).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+#[test]
+fn finds_csvs_at_any_depth() {
+    let zip_bytes = zip(&[
+        ("readme.txt", b"hi"),
+        ("My Car/My Car.csv", b"Trim Name\nRacer\n"),
+        ("Exports/2022/Old Car.CSV", b"Trim Name\nRoad\n"),
+        ("__MACOSX/My Car/._My Car.csv", b"junk"),
+    ]);
+    let candidates = archive::find_csvs(&zip_bytes, &Limits::default()).unwrap();
+    let paths: Vec<&str> = candidates.iter().map(|candidate| candidate.path.as_str()).collect();
+    assert_eq!(paths, ["My Car/My Car.csv", "Exports/2022/Old Car.CSV"]);
+    assert_eq!(candidates[0].bytes, b"Trim Name\nRacer\n");
+}
+
+#[test]
+fn choosing_a_csv() {
+    let zip_bytes = zip(&[("a/car.csv", b"1"), ("b/other car.csv", b"2")]);
+    let candidates = archive::find_csvs(&zip_bytes, &Limits::default()).unwrap();
+    assert_eq!(
+        archive::choose(candidates.clone(), None),
+        Err(ArchiveError::SeveralCsvs { paths: vec![String::from("a/car.csv"), String::from("b/other car.csv")] })
+    );
+    assert_eq!(archive::choose(candidates.clone(), Some("2")).unwrap().bytes, b"2");
+    assert_eq!(archive::choose(candidates.clone(), Some("Other Car.csv")).unwrap().bytes, b"2");
+    assert_eq!(archive::choose(candidates.clone(), Some("a/car.csv")).unwrap().bytes, b"1");
+    assert!(matches!(archive::choose(candidates.clone(), Some("3")), Err(ArchiveError::NoSuchCsv { .. })));
+    assert!(matches!(archive::choose(candidates, Some("0")), Err(ArchiveError::NoSuchCsv { .. })));
+
+    let single = archive::find_csvs(&zip(&[("car.csv", b"1")]), &Limits::default()).unwrap();
+    assert_eq!(archive::choose(single, None).unwrap().path, "car.csv");
+    let none = archive::find_csvs(&zip(&[("readme.txt", b"hi")]), &Limits::default()).unwrap();
+    assert_eq!(archive::choose(none, None), Err(ArchiveError::NoCsv));
+}
+
+#[test]
+fn rejects_unsafe_paths() {
+    for path in ["../car.csv", "/etc/car.csv", "a/../../car.csv"] {
+        let zip_bytes = zip(&[("car.csv", b"Trim Name\n"), (path, b"Trim Name\n")]);
+        assert_eq!(archive::find_csvs(&zip_bytes, &Limits::default()), Err(ArchiveError::UnsafePath { path: String::from(path) }));
+    }
+}
+
+#[test]
+fn enforces_limits() {
+    let big = vec![b'a'; 2048];
+    let zip_bytes = zip(&[("car.csv", &big)]);
+    let limits = Limits { csv_size: 1024, ..Limits::default() };
+    assert!(matches!(archive::find_csvs(&zip_bytes, &limits), Err(ArchiveError::TooLarge { .. })));
+    let zip_bytes = zip(&[("a.csv", &big), ("b.csv", &big)]);
+    let limits = Limits { total_size: 3000, ..Limits::default() };
+    assert!(matches!(archive::find_csvs(&zip_bytes, &limits), Err(ArchiveError::TooLarge { .. })));
+    let zip_bytes = zip(&[("car.csv", &big)]);
+    let limits = Limits { zip_size: 16, ..Limits::default() };
+    assert!(matches!(archive::find_csvs(&zip_bytes, &limits), Err(ArchiveError::TooLarge { .. })));
+
+    let zip_bytes = zip(&[("a.txt", b""), ("b.txt", b""), ("car.csv", b"")]);
+    let limits = Limits { entries: 2, ..Limits::default() };
+    assert_eq!(archive::find_csvs(&zip_bytes, &limits), Err(ArchiveError::TooManyEntries { entries: 3, limit: 2 }));
+    assert!(matches!(archive::find_csvs(b"not a zip", &Limits::default()), Err(ArchiveError::Unreadable { .. })));
+}
+
+#[test]
+fn file_names() {
+    assert!(archive::check_file_name("My Car.zip").is_ok());
+    assert!(archive::check_file_name("MY_CAR.ZIP").is_ok());
+    assert!(archive::check_file_name("Coupé.zip").is_ok());
+    for name in ["", ".zip", "car.csv", "../car.zip", "a/b.zip", "a\\b.zip", ".hidden.zip", "car\n.zip"] {
+        assert!(archive::check_file_name(name).is_err(), "{:?}", name);
+    }
+}
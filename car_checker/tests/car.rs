@@ -0,0 +1,51 @@
+mod common;
+
+use car_checker::car::{Aspiration, Car, Differential, Fuel, Gearbox};
+use car_checker::CheckError;
+use common::{fixture, with, without};
+
+#[test]
+fn parses_sections() {
+    let car = Car::from(&fixture("mcs_s1_group_c_turbo"));
+    assert_eq!(car.aspiration.kind.get().unwrap(), Aspiration::Turbo);
+    assert_eq!(car.aspiration.peak_boost.get().unwrap(), 1.85);
+    assert_eq!(car.engine.fuel.get().unwrap(), Fuel::Leaded);
+    assert_eq!(car.engine.octane.get().unwrap(), 110);
+    assert_eq!(car.chassis.gearbox.get().unwrap(), Gearbox::Manual);
+    assert_eq!(car.chassis.differential.get().unwrap(), Differential::GearedLsd);
+    assert_eq!(car.body.name.get().unwrap(), "LMP Closed Prototype");
+    assert_eq!(car.techpools.get("Trim Aerodynamics Techpool").unwrap(), 8);
+    assert_eq!(car.techpools.iter().count(), 21);
+    assert_eq!(car.engine_quality().map(|column| column.name.as_str()).collect::<Vec<_>>(), [
+        "Variant Aspiration Quality",
+        "Variant Bottom End Quality",
+        "Variant Exhaust Quality",
+        "Variant Fuel System Quality",
+        "Variant Top End Quality",
+    ]);
+}
+
+#[test]
+fn enums() {
+    let aspiration = |value| Car::from(&with(&fixture("mcs_s1_gt1_na"), &[("Aspiration", value)])).aspiration.kind.get().unwrap();
+    assert_eq!(aspiration("None"), Aspiration::None);
+    assert_eq!(aspiration("Naturally Aspirated"), Aspiration::NaturallyAspirated);
+    assert_eq!(aspiration("Twin Turbo"), Aspiration::TwinTurbo);
+    assert_eq!(aspiration("Quad Turbo"), Aspiration::QuadTurbo);
+    assert_eq!(aspiration("Supercharger"), Aspiration::Other(String::from("Supercharger")));
+
+    let gearbox = |value| Car::from(&with(&fixture("mcs_s1_gt1_na"), &[("Gearbox Type", value)])).chassis.gearbox.get().unwrap();
+    assert_eq!(gearbox("Adv Automatic"), Gearbox::AdvancedAutomatic);
+    assert_eq!(gearbox("Auto Manual"), Gearbox::AutoManual);
+}
+
+#[test]
+fn bad_columns_only_affect_their_field() {
+    let car = Car::from(&without(&fixture("mcs_s1_gt1_na"), "Peak Boost"));
+    assert_eq!(car.aspiration.peak_boost.get(), Err(CheckError::Missing { field: String::from("Peak Boost") }));
+    assert!(car.aspiration.turbine_size.get().is_ok());
+    assert!(car.techpools.get("Not A Techpool").is_err());
+
+    let car = Car::from(&with(&fixture("mcs_s1_gt1_na"), &[("Leaded Fuel", "maybe")]));
+    assert!(matches!(car.engine.fuel.get(), Err(CheckError::Malformed { .. })));
+}
@@ -0,0 +1,55 @@
+// Each test binary only uses some of these helpers
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use car_checker::regulations::rules::RuleSet;
+use car_checker::regulations::Regulations;
+use car_checker::CarData;
+
+/// Loads one of the synthetic UTF-16 exports in `tests/fixtures`.
+pub fn fixture(name: &str) -> CarData {
+    car_checker::from_utf16_file(&format!("{}/tests/fixtures/{}.csv", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+/// Builds a car from a field map.
+pub fn car(fields: &[(&str, &str)]) -> CarData {
+    let fields: HashMap<String, String> = fields.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    CarData::from(fields)
+}
+
+/// A fixture with some of its fields changed (or added).
+pub fn with(base: &CarData, changes: &[(&str, &str)]) -> CarData {
+    let mut fields: HashMap<String, String> = (**base).clone();
+    for (key, value) in changes {
+        fields.insert(key.to_string(), value.to_string());
+    }
+    CarData::from(fields)
+}
+
+/// A fixture with a field removed.
+pub fn without(base: &CarData, field: &str) -> CarData {
+    let mut fields: HashMap<String, String> = (**base).clone();
+    fields.remove(field);
+    CarData::from(fields)
+}
+
+pub fn rule_file(name: &str) -> RuleSet {
+    RuleSet::from_file(format!("{}/../regulations/{}.toml", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+/// The ids of every rule the car breaks, sorted and without duplicates.
+pub fn broken_rules(regulations: &dyn Regulations, car: &CarData) -> Vec<String> {
+    let mut rules: Vec<String> = regulations.check(car).violations().iter().map(|v| v.rule.clone()).collect();
+    rules.sort();
+    rules.dedup();
+    rules
+}
+
+/// Checks that both the Rust regulations and the rule file break exactly the expected rules.
+pub fn assert_breaks(rust: &dyn Regulations, rules: &RuleSet, car: &CarData, expected: &[&str]) {
+    let mut expected: Vec<String> = expected.iter().map(|rule| rule.to_string()).collect();
+    expected.sort();
+    assert_eq!(broken_rules(rust, car), expected, "Rust regulations");
+    assert_eq!(broken_rules(rules, car), expected, "{}", rules.name);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use std::collections::HashMap;
+
+use car_checker::CarData;
+use car_checker::diff::{CarDiff, Change, Delta, Section};
+use common::{car, fixture, with, without};
+
+#[test]
+fn sections() {
+    assert_eq!(Section::of("Variant Top End Quality"), Section::Engine);
+    assert_eq!(Section::of("Peak Boost"), Section::Engine);
+    assert_eq!(Section::of("Leaded Fuel"), Section::Engine);
+    assert_eq!(Section::of("Chassis Material"), Section::Chassis);
+    assert_eq!(Section::of("Trim Brake Techpool"), Section::Chassis);
+    assert_eq!(Section::of("Front Downforce"), Section::Aero);
+    assert_eq!(Section::of("Trim Aerodynamics Techpool"), Section::Aero);
+    assert_eq!(Section::of("Body Name"), Section::Trim);
+    assert_eq!(Section::of("Safety Rating"), Section::Trim);
+    assert_eq!(Section::of("Trim Fixture Techpool"), Section::Trim);
+}
+
+#[test]
+fn added_removed_and_changed() {
+    let old = fixture("mcs_s1_gt1_turbo");
+    let new = with(&without(&old, "Active Aero"), &[("Peak Boost", "1.5"), ("Chassis Material", "Carbon Fibre"), ("Cup Holders", "2")]);
+    let diff = CarDiff::between(&old, &new);
+    assert_eq!(diff.fields.len(), 4);
+    assert_eq!(diff.get("Active Aero").unwrap().change, Change::Removed { value: old.get("Active Aero").unwrap() });
+    assert_eq!(diff.get("Cup Holders").unwrap().change, Change::Added { value: String::from("2") });
+    assert!(matches!(&diff.get("Chassis Material").unwrap().change, Change::Changed { delta: None, new, .. } if new == "Carbon Fibre"));
+    let fields: Vec<&str> = diff.fields.iter().map(|diff| diff.field.as_str()).collect();
+    assert_eq!(fields, ["Peak Boost", "Chassis Material", "Active Aero", "Cup Holders"]);
+    assert!(CarDiff::between(&old, &old).is_empty());
+}
+
+#[test]
+fn numeric_deltas() {
+    let diff = CarDiff::between(&car(&[("Peak Boost", "1.2"), ("Cylinder Count", "0")]), &car(&[("Peak Boost", "0.9"), ("Cylinder Count", "12")]));
+    let delta = |field| match &diff.get(field).unwrap().change {
+        Change::Changed { delta: Some(delta), .. } => *delta,
+        change => panic!("{:?}", change),
+    };
+    assert_eq!(delta("Peak Boost").to_string(), "-0.3, -25.0%");
+    assert_eq!(delta("Cylinder Count"), Delta { absolute: 12.0, percent: None });
+    assert_eq!(delta("Cylinder Count").to_string(), "+12");
+}
+
+#[test]
+fn renders() {
+    let old = car(&[("Peak Boost", "1.2"), ("Body Name", "A|B")]);
+    let new = car(&[("Peak Boost", "1.5"), ("Body Name", "C")]);
+    let diff = CarDiff::between(&old, &new);
+
+    let markdown = diff.to_markdown();
+    assert!(markdown.contains("## Engine\n"));
+    assert!(markdown.contains("| Peak Boost | 1.2 | 1.5 | +0.3, +25.0% |"));
+    assert!(markdown.contains("| Body Name | A\\|B | C |  |"));
+    assert!(markdown.find("## Engine").unwrap() < markdown.find("## Trim").unwrap());
+
+    let json: serde_json::Value = serde_json::from_str(&diff.to_json()).unwrap();
+    assert_eq!(json["fields"][0]["section"], "engine");
+    assert_eq!(json["fields"][0]["kind"], "changed");
+    assert_eq!(json["fields"][0]["delta"]["absolute"].as_f64().unwrap().round(), 0.0);
+
+    let fields = diff.to_embed_fields();
+    assert_eq!(fields[0], (String::from("Engine (1)"), String::from("**Peak Boost**: 1.2 → 1.5 (+0.3, +25.0%)\n"), false));
+    assert_eq!(fields[1].0, "Trim (1)");
+}
+
+#[test]
+fn embed_fields_stay_under_the_limit() {
+    let car = |value: &str| CarData::from((0..100).map(|i| (format!("Variant Field {}", i), String::from(value))).collect::<HashMap<_, _>>());
+    let diff = CarDiff::between(&car("old value"), &car("new value"));
+    let fields = diff.to_embed_fields();
+    assert_eq!(fields.len(), 1);
+    assert!(fields[0].1.chars().count() <= 1024);
+    assert!(fields[0].1.contains("more"));
+}
@@ -0,0 +1,62 @@
+use car_checker::encoding::{decode, Encoding};
+
+const CSV: &str = "Trim Name,Body Name\r\nRacer,LMP Closed Prototype\r\n";
+
+fn utf16le(text: &str) -> Vec<u8> {
+    text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
+
+fn utf16be(text: &str) -> Vec<u8> {
+    text.encode_utf16().flat_map(u16::to_be_bytes).collect()
+}
+
+fn with_bom(bom: &[u8], bytes: Vec<u8>) -> Vec<u8> {
+    bom.iter().copied().chain(bytes).collect()
+}
+
+#[test]
+fn detects_encoding() {
+    assert_eq!(Encoding::detect(&utf16le(CSV)), (Encoding::Utf16Le, 0));
+    assert_eq!(Encoding::detect(&utf16be(CSV)), (Encoding::Utf16Be, 0));
+    assert_eq!(Encoding::detect(CSV.as_bytes()), (Encoding::Utf8, 0));
+    assert_eq!(Encoding::detect(&with_bom(&[0xFF, 0xFE], utf16le(CSV))), (Encoding::Utf16Le, 2));
+    assert_eq!(Encoding::detect(&with_bom(&[0xFE, 0xFF], utf16be(CSV))), (Encoding::Utf16Be, 2));
+    assert_eq!(Encoding::detect(&with_bom(&[0xEF, 0xBB, 0xBF], CSV.as_bytes().to_vec())), (Encoding::Utf8, 3));
+}
+
+#[test]
+fn decodes_and_strips_bom() {
+    for bytes in [
+        utf16le(CSV),
+        utf16be(CSV),
+        CSV.as_bytes().to_vec(),
+        with_bom(&[0xFF, 0xFE], utf16le(CSV)),
+        with_bom(&[0xFE, 0xFF], utf16be(CSV)),
+        with_bom(&[0xEF, 0xBB, 0xBF], CSV.as_bytes().to_vec()),
+    ] {
+        assert_eq!(decode(&bytes).unwrap(), CSV);
+        let car = car_checker::from_bytes(&bytes).unwrap();
+        assert_eq!(car.get("Trim Name").unwrap(), "Racer");
+    }
+}
+
+#[test]
+fn decodes_misaligned_buffers() {
+    // The decoder must not care where the buffer starts in memory
+    let mut bytes = vec![0];
+    bytes.extend(utf16le(CSV));
+    assert_eq!(decode(&bytes[1..]).unwrap(), CSV);
+}
+
+#[test]
+fn odd_byte_count_is_an_error() {
+    let mut bytes = utf16le(CSV);
+    bytes.pop();
+    let error = decode(&bytes).unwrap_err().to_string();
+    assert!(error.contains("odd number of bytes"), "{}", error);
+}
+
+#[test]
+fn invalid_utf8_is_an_error() {
+    assert!(decode(&[b'a', 0xC3, b'b', 0xFF]).is_err());
+}
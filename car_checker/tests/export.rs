@@ -0,0 +1,40 @@
+use car_checker::{Export, ExportError, Trim};
+
+const MULTI: &str = "Model Name,Trim Name,Variant Name,Safety Rating\n\
+Dysoon,Racer,V12,55\n\
+Dysoon,Road,V12,40\n\
+Dysoon,Racer,V8,60\n";
+
+#[test]
+fn exposes_every_row() {
+    let export = Export::parse(MULTI).unwrap();
+    assert_eq!(export.rows().len(), 3);
+    assert_eq!(export.trims(), ["1. Dysoon Racer (V12)", "2. Dysoon Road (V12)", "3. Dysoon Racer (V8)"]);
+}
+
+#[test]
+fn select_by_name_or_index() {
+    let export = Export::parse(MULTI).unwrap();
+    assert_eq!(export.select(Trim::Name("road")).unwrap().get("Safety Rating").unwrap(), "40");
+    assert_eq!(export.select(Trim::Index(2)).unwrap().get("Safety Rating").unwrap(), "60");
+    assert_eq!(
+        export.select(Trim::Name("Racer")).unwrap_err(),
+        ExportError::Ambiguous { trims: vec![String::from("1. Dysoon Racer (V12)"), String::from("3. Dysoon Racer (V8)")] }
+    );
+    assert!(matches!(export.select(Trim::Name("Sport")), Err(ExportError::NoSuchTrim { .. })));
+    assert!(matches!(export.select(Trim::Index(3)), Err(ExportError::NoSuchTrim { .. })));
+}
+
+#[test]
+fn multiple_trims_are_never_picked_silently() {
+    let export = Export::parse(MULTI).unwrap();
+    assert!(matches!(export.single(), Err(ExportError::Ambiguous { .. })));
+    assert!(car_checker::from_utf8_string(String::from(MULTI)).is_err());
+}
+
+#[test]
+fn single_trim() {
+    let export = Export::parse("Trim Name,Safety Rating\nRacer,55\n").unwrap();
+    assert_eq!(export.single().unwrap().get("Trim Name").unwrap(), "Racer");
+    assert_eq!(Export::parse("Trim Name\n").unwrap().single().unwrap_err(), ExportError::Empty);
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use car_checker::regulations::mcs_s1_group_c::MCS_S1_Group_C;
+use car_checker::CarData;
+use common::{assert_breaks, fixture, rule_file, with, without};
+
+fn turbo() -> CarData {
+    fixture("mcs_s1_group_c_turbo")
+}
+
+fn na() -> CarData {
+    fixture("mcs_s1_group_c_na")
+}
+
+fn assert_group_c(car: &CarData, expected: &[&str]) {
+    assert_breaks(&MCS_S1_Group_C, &rule_file("mcs_s1_group_c"), car, expected);
+}
+
+#[test]
+fn fixtures_pass() {
+    assert_group_c(&turbo(), &[]);
+    assert_group_c(&na(), &[]);
+    // Both ways of writing "no turbo" count as naturally aspirated
+    assert_group_c(&with(&na(), &[("Aspiration", "None")]), &[]);
+}
+
+#[test]
+fn body_and_years() {
+    assert_group_c(&with(&na(), &[("Body Name", "GT Coupe 2 Door")]), &["body.name"]);
+    assert_group_c(&with(&na(), &[("Variant Year", "1996")]), &["year.engine"]);
+    assert_group_c(&with(&na(), &[("Model Year", "1994")]), &["year.body"]);
+    assert_group_c(&with(&na(), &[("Small 1st Row Seats", "1")]), &["body.small_seats"]);
+}
+
+#[test]
+fn quality_slider_bounds() {
+    // Only engine sliders are limited, on both turbo and NA engines
+    assert_group_c(&with(&na(), &[("Trim Body Quality", "15")]), &[]);
+    assert_group_c(&with(&na(), &[("Variant Top End Quality", "5.9"), ("Variant Exhaust Quality", "-5.9")]), &[]);
+    assert_group_c(&with(&na(), &[("Variant Top End Quality", "6")]), &["engine.quality_sliders"]);
+    assert_group_c(&with(&turbo(), &[("Variant Exhaust Quality", "-6")]), &["engine.quality_sliders"]);
+}
+
+#[test]
+fn stats() {
+    assert_group_c(&with(&na(), &[("Safety Rating", "49.5")]), &[]);
+    assert_group_c(&with(&na(), &[("Safety Rating", "49.4")]), &["stats.safety"]);
+    // Economy is rounded to a whole number first, so 23.5 already counts as 24
+    assert_group_c(&with(&na(), &[("Trim Economy", "23.4")]), &[]);
+    assert_group_c(&with(&na(), &[("Trim Economy", "23.5")]), &["stats.economy"]);
+    assert_group_c(&with(&na(), &[("Rear Downforce", "850.9")]), &[]);
+    assert_group_c(&with(&na(), &[("Rear Downforce", "851")]), &["stats.rear_downforce"]);
+    assert_group_c(&with(&na(), &[("Front Downforce", "851")]), &["stats.front_downforce"]);
+}
+
+#[test]
+fn banned_parts() {
+    assert_group_c(&with(&na(), &[("Active Aero", "Active Wing")]), &["banned.active_aero"]);
+    assert_group_c(&with(&na(), &[("Gearbox Type", "Adv Automatic")]), &["banned.gearbox"]);
+    assert_group_c(&with(&na(), &[("Gearbox Type", "Auto Manual")]), &["banned.gearbox"]);
+    assert_group_c(&with(&na(), &[("Gearbox Type", "Automatic")]), &[]);
+    assert_group_c(&with(&na(), &[("Differential Type", "Open")]), &["banned.differential"]);
+    // Materials and suspension are free in Group C
+    assert_group_c(&with(&na(), &[("Chassis Material", "Carbon Fibre"), ("Rear Suspension", "Pushrod")]), &[]);
+}
+
+#[test]
+fn fuel_and_exhaust() {
+    assert_group_c(&with(&na(), &[("Leaded Fuel", "FALSE")]), &["engine.leaded_fuel"]);
+    assert_group_c(&with(&na(), &[("Fuel Octane", "98")]), &["engine.octane"]);
+    assert_group_c(&with(&na(), &[("Muffler 1", "None")]), &["engine.mufflers"]);
+    assert_group_c(&with(&turbo(), &[("Muffler 2", "None")]), &["engine.mufflers"]);
+}
+
+#[test]
+fn turbo_engine() {
+    assert_group_c(&with(&turbo(), &[("Variant Aspiration Quality", "1")]), &["engine.turbo.quality"]);
+    assert_group_c(&with(&turbo(), &[("Peak Boost", "2.07")]), &["engine.turbo.boost"]);
+    assert_group_c(&with(&turbo(), &[("Compressor Size 1", "90.5")]), &["engine.turbo.compressor"]);
+    assert_group_c(&with(&turbo(), &[("Turbine Size 1", "75.5")]), &["engine.turbo.turbine"]);
+    assert_group_c(&with(&turbo(), &[("Engine Reliability", "54.95")]), &[]);
+    assert_group_c(&with(&turbo(), &[("Engine Reliability", "54.9")]), &["engine.turbo.reliability"]);
+    // Twin turbos are allowed in Group C
+    assert_group_c(&with(&turbo(), &[("Aspiration", "Twin Turbo")]), &[]);
+}
+
+#[test]
+fn turbo_displacement_edges() {
+    assert_group_c(&with(&turbo(), &[("Family Displacement", "1.6"), ("Variant Displacement", "1.6009")]), &[]);
+    assert_group_c(&with(&turbo(), &[("Variant Displacement", "1.601")]), &["engine.turbo.displacement"]);
+    assert_group_c(&with(&turbo(), &[("Family Displacement", "1.601")]), &["engine.turbo.displacement"]);
+}
+
+#[test]
+fn na_engine() {
+    assert_group_c(&with(&na(), &[("Family Displacement", "3.8"), ("Variant Displacement", "3.8")]), &[]);
+    assert_group_c(&with(&na(), &[("Variant Displacement", "3.81")]), &["engine.na.displacement"]);
+    assert_group_c(&with(&na(), &[("Engine Reliability", "39.95")]), &[]);
+    assert_group_c(&with(&na(), &[("Engine Reliability", "39.9")]), &["engine.na.reliability"]);
+    // Turbo-only limits don't apply
+    assert_group_c(&with(&na(), &[("Variant Aspiration Quality", "3"), ("Peak Boost", "3")]), &[]);
+}
+
+#[test]
+fn missing_columns() {
+    assert_group_c(&without(&na(), "Engine Reliability"), &["engine.na.reliability"]);
+    assert_group_c(&without(&na(), "Aspiration"), &["turbo"]);
+}
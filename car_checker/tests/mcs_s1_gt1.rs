@@ -0,0 +1,135 @@
+mod common;
+
+use car_checker::regulations::mcs_s1_gt1::MCS_S1_GT1;
+use car_checker::CarData;
+use common::{assert_breaks, fixture, rule_file, with, without};
+
+fn turbo() -> CarData {
+    fixture("mcs_s1_gt1_turbo")
+}
+
+fn na() -> CarData {
+    fixture("mcs_s1_gt1_na")
+}
+
+fn assert_gt1(car: &CarData, expected: &[&str]) {
+    assert_breaks(&MCS_S1_GT1, &rule_file("mcs_s1_gt1"), car, expected);
+}
+
+#[test]
+fn fixtures_pass() {
+    assert_gt1(&turbo(), &[]);
+    assert_gt1(&na(), &[]);
+}
+
+#[test]
+fn body_and_years() {
+    assert_gt1(&with(&turbo(), &[("Body Name", "LMP Closed Prototype")]), &["body.name"]);
+    assert_gt1(&with(&turbo(), &[("Variant Year", "1996")]), &["year.engine"]);
+    assert_gt1(&with(&turbo(), &[("Engine Family Year", "1994")]), &["year.engine"]);
+    assert_gt1(&with(&turbo(), &[("Trim Year", "1996")]), &["year.body"]);
+    assert_gt1(&with(&turbo(), &[("Model Year", "1994")]), &["year.body"]);
+    assert_gt1(&with(&turbo(), &[("Small 2nd Row Seats", "2")]), &["body.small_seats"]);
+}
+
+#[test]
+fn quality_slider_bounds() {
+    // Sliders are truncated, so anything short of 11 is still +10
+    assert_gt1(&with(&na(), &[("Trim Body Quality", "10.9"), ("Trim Chassis Quality", "-10.9")]), &[]);
+    assert_gt1(&with(&na(), &[("Trim Body Quality", "11")]), &["stats.quality"]);
+    assert_gt1(&with(&na(), &[("Trim Chassis Quality", "-11")]), &["stats.quality"]);
+
+    // Engine sliders are only limited to +/- 5 on turbo engines
+    assert_gt1(&with(&na(), &[("Variant Bottom End Quality", "8")]), &[]);
+    assert_gt1(&with(&turbo(), &[("Variant Bottom End Quality", "5.9"), ("Variant Top End Quality", "-5.9")]), &[]);
+    assert_gt1(&with(&turbo(), &[("Variant Bottom End Quality", "6")]), &["engine.turbo.quality_sliders"]);
+    assert_gt1(&with(&turbo(), &[("Variant Top End Quality", "-6")]), &["engine.turbo.quality_sliders"]);
+}
+
+#[test]
+fn stats() {
+    assert_gt1(&with(&turbo(), &[("Safety Rating", "54.5")]), &[]);
+    assert_gt1(&with(&turbo(), &[("Safety Rating", "54.4")]), &["stats.safety"]);
+    assert_gt1(&with(&turbo(), &[("Trim Economy", "15.4")]), &[]);
+    assert_gt1(&with(&turbo(), &[("Trim Economy", "15.5")]), &["stats.economy"]);
+    assert_gt1(&with(&turbo(), &[("Front Downforce", "250.9")]), &[]);
+    assert_gt1(&with(&turbo(), &[("Front Downforce", "251")]), &["stats.front_downforce"]);
+    assert_gt1(&with(&turbo(), &[("Rear Downforce", "251")]), &["stats.rear_downforce"]);
+}
+
+#[test]
+fn banned_parts() {
+    assert_gt1(&with(&turbo(), &[("Active Aero", "Active Wing")]), &["banned.active_aero"]);
+    assert_gt1(&with(&turbo(), &[("Gearbox Type", "Adv Automatic")]), &["banned.gearbox"]);
+    assert_gt1(&with(&turbo(), &[("Gearbox Type", "Auto Manual")]), &[]);
+    assert_gt1(&with(&turbo(), &[("Differential Type", "Open")]), &["banned.differential"]);
+    assert_gt1(&with(&turbo(), &[("Chassis Material", "Glued Aluminium")]), &["banned.chassis_material"]);
+    assert_gt1(&with(&turbo(), &[("Chassis Material", "Carbon Fibre")]), &["banned.chassis_material"]);
+    assert_gt1(&with(&turbo(), &[("Panel Material", "Carbon Fibre")]), &["banned.panel_material"]);
+    assert_gt1(&with(&turbo(), &[("Rear Suspension", "Pushrod")]), &["banned.pushrod"]);
+}
+
+#[test]
+fn fuel() {
+    assert_gt1(&with(&turbo(), &[("Leaded Fuel", "TRUE")]), &["engine.leaded_fuel"]);
+    assert_gt1(&with(&turbo(), &[("Fuel Octane", "98.7")]), &[]);
+    assert_gt1(&with(&turbo(), &[("Fuel Octane", "95")]), &["engine.octane"]);
+}
+
+#[test]
+fn turbo_engine() {
+    assert_gt1(&with(&turbo(), &[("Aspiration", "Twin Turbo")]), &["engine.turbo.count"]);
+    assert_gt1(&with(&turbo(), &[("Aspiration", "Quad Turbo")]), &["engine.turbo.count"]);
+    assert_gt1(&with(&turbo(), &[("Cylinder Count", "8")]), &[]);
+    assert_gt1(&with(&turbo(), &[("Cylinder Count", "10")]), &["engine.turbo.cylinders"]);
+    assert_gt1(&with(&turbo(), &[("Intake", "Race")]), &["engine.turbo.intake"]);
+    assert_gt1(&with(&turbo(), &[("Variant Aspiration Quality", "1")]), &["engine.turbo.quality"]);
+    assert_gt1(&with(&turbo(), &[("Peak Boost", "2.06")]), &[]);
+    assert_gt1(&with(&turbo(), &[("Peak Boost", "2.07")]), &["engine.turbo.boost"]);
+    assert_gt1(&with(&turbo(), &[("Compressor Size 1", "90")]), &[]);
+    assert_gt1(&with(&turbo(), &[("Compressor Size 1", "90.5")]), &["engine.turbo.compressor"]);
+    assert_gt1(&with(&turbo(), &[("Turbine Size 1", "75")]), &[]);
+    assert_gt1(&with(&turbo(), &[("Turbine Size 1", "75.5")]), &["engine.turbo.turbine"]);
+    assert_gt1(&with(&turbo(), &[("Engine Reliability", "54.95")]), &[]);
+    assert_gt1(&with(&turbo(), &[("Engine Reliability", "54.9")]), &["engine.turbo.reliability"]);
+}
+
+#[test]
+fn turbo_displacement_edges() {
+    // Displacement is compared in whole cc, rounded down
+    assert_gt1(&with(&turbo(), &[("Family Displacement", "1.7"), ("Variant Displacement", "1.7")]), &[]);
+    assert_gt1(&with(&turbo(), &[("Family Displacement", "1.7009"), ("Variant Displacement", "1.7009")]), &[]);
+    assert_gt1(&with(&turbo(), &[("Variant Displacement", "1.701")]), &["engine.turbo.displacement"]);
+    assert_gt1(&with(&turbo(), &[("Family Displacement", "1.701")]), &["engine.turbo.displacement"]);
+}
+
+#[test]
+fn na_engine() {
+    assert_gt1(&with(&na(), &[("Family Displacement", "4.5"), ("Variant Displacement", "4.5")]), &[]);
+    assert_gt1(&with(&na(), &[("Variant Displacement", "4.51")]), &["engine.na.displacement"]);
+    assert_gt1(&with(&na(), &[("Engine Reliability", "44.95")]), &[]);
+    assert_gt1(&with(&na(), &[("Engine Reliability", "44.9")]), &["engine.na.reliability"]);
+    // Turbo-only limits don't apply
+    assert_gt1(&with(&na(), &[("Intake", "Race"), ("Cylinder Count", "12")]), &[]);
+}
+
+#[test]
+fn na_race_headers() {
+    assert_gt1(&with(&na(), &[("Headers", "Tubular Race")]), &["engine.na.headers"]);
+    assert_gt1(&with(&na(), &[("Headers", "Tubular Race"), ("Family Displacement", "3.3"), ("Variant Displacement", "3.3")]), &[]);
+    assert_gt1(&with(&na(), &[("Headers", "Tubular Race"), ("Family Displacement", "3.3"), ("Variant Displacement", "3.31")]), &["engine.na.headers"]);
+    // Race headers are fine on turbo engines
+    assert_gt1(&with(&turbo(), &[("Headers", "Tubular Race")]), &[]);
+}
+
+#[test]
+fn every_violation_is_reported() {
+    let car = with(&turbo(), &[("Active Aero", "Active Wing"), ("Peak Boost", "2.5"), ("Model Year", "1999"), ("Trim Brake Techpool", "6")]);
+    assert_gt1(&car, &["banned.active_aero", "engine.turbo.boost", "techpool", "year.body"]);
+}
+
+#[test]
+fn missing_columns() {
+    assert_gt1(&without(&turbo(), "Peak Boost"), &["engine.turbo.boost"]);
+    assert_gt1(&without(&turbo(), "Aspiration"), &["turbo"]);
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use car_checker::diff::CarDiff;
+use car_checker::regulations::rules::RuleSet;
+use common::{car, fixture, rule_file, with};
+
+fn flagged(rules: &RuleSet, old: &car_checker::CarData, new: &car_checker::CarData) -> Vec<(String, String)> {
+    rules.review(&CarDiff::between(old, new), new).flags.into_iter().map(|flag| (flag.rule, flag.field)).collect()
+}
+
+#[test]
+fn frozen_components_are_flagged() {
+    let rules = rule_file("mcs_s1_gt1");
+    let old = fixture("mcs_s1_gt1_turbo");
+
+    let tuned = with(&old, &[("Peak Boost", "1.5"), ("Front Downforce", "120")]);
+    assert!(rules.review(&CarDiff::between(&old, &tuned), &tuned).is_clean());
+
+    let new = with(&old, &[("Body Name", "Another Body"), ("Chassis Material", "Carbon Fibre"), ("Peak Boost", "1.5")]);
+    let review = rules.review(&CarDiff::between(&old, &new), &new);
+    assert_eq!(flagged(&rules, &old, &new), [
+        (String::from("frozen.body"), String::from("Body Name")),
+        (String::from("frozen.chassis_material"), String::from("Chassis Material")),
+    ]);
+    assert!(review.to_markdown().contains("The body can't change during the season"));
+}
+
+#[test]
+fn selectors_and_conditions() {
+    let rules = RuleSet::parse(r#"
+        name = "Test"
+
+        [conditions.turbo]
+        field = "Aspiration"
+        starts_with = "Turbo"
+
+        [[frozen]]
+        id = "frozen.aero"
+        section = "aero"
+        message = "{field} is frozen"
+
+        [[frozen]]
+        id = "frozen.turbo"
+        when = ["turbo"]
+        fields_containing = ["Turbine"]
+        message = "Turbos are frozen"
+    "#).unwrap();
+
+    let old = car(&[("Aspiration", "Turbo"), ("Front Downforce", "100"), ("Turbine Size", "40"), ("Cup Holders", "1")]);
+    let new = with(&old, &[("Front Downforce", "110"), ("Turbine Size", "42"), ("Cup Holders", "2")]);
+    let review = rules.review(&CarDiff::between(&old, &new), &new);
+    assert_eq!(review.flags.len(), 2);
+    assert_eq!(review.flags[0].message, "Front Downforce is frozen");
+    assert_eq!(review.flags[1].rule, "frozen.turbo");
+
+    let na_old = with(&old, &[("Aspiration", "Natural")]);
+    let na_new = with(&new, &[("Aspiration", "Natural")]);
+    assert_eq!(flagged(&rules, &na_old, &na_new), [(String::from("frozen.aero"), String::from("Front Downforce"))]);
+}
+
+#[test]
+fn frozen_entries_are_validated() {
+    let parse = |frozen: &str| RuleSet::parse(&format!("name = \"Test\"\n[[frozen]]\n{}", frozen));
+    assert!(parse("id = \"a\"\nfield = \"Body Name\"\nmessage = \"m\"").is_ok());
+    assert!(parse("field = \"Body Name\"\nmessage = \"m\"").is_err());
+    assert!(parse("id = \"a\"\nfield = \"Body Name\"").is_err());
+    assert!(parse("id = \"a\"\nfield = \"Body Name\"\nmessage = \"m\"\nis = \"x\"").is_err());
+    assert!(parse("id = \"a\"\nfield = \"Body Name\"\nsection = \"trim\"\nmessage = \"m\"").is_err());
+    assert!(parse("id = \"a\"\nfield = \"Body Name\"\nmessage = \"m\"\nwhen = [\"turbo\"]").is_err());
+}
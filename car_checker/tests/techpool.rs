@@ -0,0 +1,106 @@
+mod common;
+
+use car_checker::regulations::mcs_s1_group_c::MCS_S1_Group_C;
+use car_checker::regulations::mcs_s1_gt1::MCS_S1_GT1;
+use car_checker::regulations::Regulations;
+use car_checker::report::{Category, Report};
+use car_checker::CarData;
+use common::rule_file;
+
+const GROUP_C_TECHPOOLS: &[(&str, usize)] = &[
+    ("Trim Interior Techpool", 5),
+    ("Variant Top End Techpool", 5),
+    ("Variant Bottom End Techpool", 5),
+    ("Family Top End Techpool", 5),
+    ("Variant Exhaust Techpool", 5),
+    ("Family Techpool", 5),
+    ("Model Chassis Techpool", 7),
+    ("Trim Assist Techpool", 5),
+    ("Trim Body Techpool", 6),
+    ("Trim Chassis Techpool", 7),
+    ("Trim Brake Techpool", 5),
+    ("Variant Fuel System Techpool", 5),
+    ("Model Body Techpool", 6),
+    ("Trim Tyre Techpool", 7),
+    ("Variant Aspiration Techpool", 5),
+    ("Trim Drivetrain Techpool", 6),
+    ("Trim Fixture Techpool", 5),
+    ("Trim Suspension Techpool", 5),
+    ("Variant Family Techpool", 5),
+    ("Trim Aerodynamics Techpool", 8),
+    ("Trim Safety Techpool", 5),
+];
+
+fn car_with_techpools(techpools: &[(&str, usize)]) -> CarData {
+    let values: Vec<(&str, String)> = techpools.iter().map(|(field, value)| (*field, value.to_string())).collect();
+    let fields: Vec<(&str, &str)> = values.iter().map(|(field, value)| (*field, value.as_str())).collect();
+    common::car(&fields)
+}
+
+fn techpool_fields(report: &Report) -> Vec<String> {
+    report.violations().iter().filter(|v| v.category == Category::Techpool).map(|v| v.field().to_string()).collect()
+}
+
+#[test]
+fn group_c_accepts_on_spec_techpools() {
+    let car = car_with_techpools(GROUP_C_TECHPOOLS);
+    assert!(techpool_fields(&MCS_S1_Group_C.check(&car)).is_empty());
+    assert!(techpool_fields(&rule_file("mcs_s1_group_c").check(&car)).is_empty());
+}
+
+#[test]
+fn group_c_rejects_off_spec_techpools() {
+    // Every column, one at a time, so none of them can silently pass again
+    for (field, required) in GROUP_C_TECHPOOLS {
+        let techpools: Vec<(&str, usize)> = GROUP_C_TECHPOOLS.iter().map(|(f, v)| if f == field { (*f, required + 1) } else { (*f, *v) }).collect();
+        let car = car_with_techpools(&techpools);
+        assert_eq!(techpool_fields(&MCS_S1_Group_C.check(&car)), vec![field.to_string()]);
+        assert_eq!(techpool_fields(&rule_file("mcs_s1_group_c").check(&car)), vec![field.to_string()]);
+    }
+}
+
+#[test]
+fn gt1_rejects_off_spec_techpools() {
+    let all_fives: Vec<(&str, usize)> = GROUP_C_TECHPOOLS.iter().map(|(field, _)| (*field, 5)).collect();
+    let car = car_with_techpools(&all_fives);
+    assert!(techpool_fields(&MCS_S1_GT1.check(&car)).is_empty());
+    assert!(techpool_fields(&rule_file("mcs_s1_gt1").check(&car)).is_empty());
+
+    // The Group C techpools are off-spec for GT1
+    let car = car_with_techpools(GROUP_C_TECHPOOLS);
+    let mut expected: Vec<String> = GROUP_C_TECHPOOLS.iter().filter(|(_, v)| *v != 5).map(|(f, _)| f.to_string()).collect();
+    expected.sort();
+    assert_eq!(techpool_fields(&MCS_S1_GT1.check(&car)), expected);
+    assert_eq!(techpool_fields(&rule_file("mcs_s1_gt1").check(&car)), expected);
+}
+
+#[test]
+fn gt1_rejects_off_spec_unlisted_techpools() {
+    let mut techpools: Vec<(&str, usize)> = GROUP_C_TECHPOOLS.iter().map(|(field, _)| (*field, 5)).collect();
+    techpools.push(("Trim Livery Techpool", 5));
+    let car = car_with_techpools(&techpools);
+    assert!(techpool_fields(&MCS_S1_GT1.check(&car)).is_empty());
+    assert!(techpool_fields(&rule_file("mcs_s1_gt1").check(&car)).is_empty());
+
+    techpools.pop();
+    techpools.push(("Trim Livery Techpool", 6));
+    let car = car_with_techpools(&techpools);
+    assert_eq!(techpool_fields(&MCS_S1_GT1.check(&car)), vec![String::from("Trim Livery Techpool")]);
+    assert_eq!(techpool_fields(&rule_file("mcs_s1_gt1").check(&car)), vec![String::from("Trim Livery Techpool")]);
+
+    // Group C only checks the columns it lists
+    let mut techpools = GROUP_C_TECHPOOLS.to_vec();
+    techpools.push(("Trim Livery Techpool", 6));
+    let car = car_with_techpools(&techpools);
+    assert!(techpool_fields(&MCS_S1_Group_C.check(&car)).is_empty());
+    assert!(techpool_fields(&rule_file("mcs_s1_group_c").check(&car)).is_empty());
+}
+
+#[test]
+fn missing_techpool_column_is_reported_as_data_problem() {
+    let car = car_with_techpools(&GROUP_C_TECHPOOLS[1..]);
+    let report = MCS_S1_Group_C.check(&car);
+    let violation = report.violations().iter().find(|v| v.category == Category::Techpool).unwrap();
+    assert_eq!(violation.field(), "Trim Interior Techpool");
+    assert!(!violation.is_breach());
+}
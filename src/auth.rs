@@ -0,0 +1,288 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Serialize, Deserialize};
+use serenity::framework::standard::macros::check;
+use serenity::framework::standard::{Args, CommandOptions, Reason};
+use serenity::model::channel::Message;
+use serenity::model::Permissions;
+use serenity::prelude::Context;
+
+use crate::series;
+use crate::storage::{Data, Store};
+
+/// Something only some members may do. Each is granted to roles, for every series or just one.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// Events, results and the leaderboard posts.
+    ResultsEditor,
+    /// Looking through, comparing and rolling back submissions.
+    SubmissionsReviewer,
+    /// Submission windows, entry limits, deadlines, rounds and who may do what.
+    SeasonAdmin,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 3] = [Capability::ResultsEditor, Capability::SubmissionsReviewer, Capability::SeasonAdmin];
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Capability::ResultsEditor => "results_editor",
+            Capability::SubmissionsReviewer => "submissions_reviewer",
+            Capability::SeasonAdmin => "season_admin",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Capability {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        Capability::ALL.into_iter().find(|capability| capability.to_string().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| format!("`{}` isn't one of {}", name, Capability::ALL.map(|capability| format!("`{}`", capability)).join(", ")))
+    }
+}
+
+/// Which roles have which capabilities.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Roles {
+    /// Role ids per capability, in every series.
+    pub global: BTreeMap<Capability, BTreeSet<u64>>,
+    /// Role ids per capability for a single series, like the EMs of that series.
+    pub series: BTreeMap<String, BTreeMap<Capability, BTreeSet<u64>>>,
+}
+
+impl Roles {
+    /// The roles with a capability in a series, or in every series if `series_id` is `None`.
+    fn granted(&self, capability: Capability, series_id: Option<&str>) -> impl Iterator<Item = &u64> {
+        let global = self.global.get(&capability).into_iter().flatten();
+        let series = series_id.and_then(|series_id| self.series.get(series_id)).and_then(|capabilities| capabilities.get(&capability));
+        global.chain(series.into_iter().flatten())
+    }
+
+    fn is_configured(&self, capability: Capability, series_id: Option<&str>) -> bool {
+        self.granted(capability, series_id).next().is_some()
+    }
+
+    fn roles_mut(&mut self, capability: Capability, series_id: Option<&str>) -> &mut BTreeSet<u64> {
+        match series_id {
+            Some(series_id) => self.series.entry(String::from(series_id)).or_default().entry(capability).or_default(),
+            None => self.global.entry(capability).or_default(),
+        }
+    }
+
+    pub fn grant(&mut self, capability: Capability, role: u64, series_id: Option<&str>) -> bool {
+        self.roles_mut(capability, series_id).insert(role)
+    }
+
+    pub fn revoke(&mut self, capability: Capability, role: u64, series_id: Option<&str>) -> bool {
+        self.roles_mut(capability, series_id).remove(&role)
+    }
+}
+
+/// What's known about the member running a command.
+#[derive(Debug, Clone, Default)]
+pub struct Member {
+    pub roles: Vec<u64>,
+    pub permissions: Permissions,
+}
+
+/// Decides whether `member` may use `capability` in a series, or across every series if
+/// `series_id` is `None`. Server administrators always may, so there's someone to hand out the
+/// roles. As long as no role has been given the capability, members who can manage roles may
+/// use it, as they could before roles were configurable.
+pub fn authorize(roles: &Roles, member: &Member, capability: Capability, series_id: Option<&str>) -> Result<(), String> {
+    if member.permissions.administrator() {
+        return Ok(());
+    }
+    let allowed = if roles.is_configured(capability, series_id) {
+        roles.granted(capability, series_id).any(|role| member.roles.contains(role))
+    } else {
+        member.permissions.manage_roles()
+    };
+    if allowed {
+        Ok(())
+    } else {
+        match series_id {
+            Some(series_id) => Err(format!("You need a role with `{}` for `{}` to do that!", capability, series_id)),
+            None => Err(format!("You need a role with `{}` to do that!", capability)),
+        }
+    }
+}
+
+/// The series a command is about, going by its first argument: either a series or leaderboard id,
+/// or a submitted version, which belongs to the series it was submitted for.
+pub fn series_of(data: &Data, first_arg: &str) -> Option<String> {
+    let first_arg = first_arg.trim().to_lowercase();
+    let is_leaderboard = data.leaderboards.guilds.values().any(|guild| guild.contains_key(&first_arg));
+    if series::find(&first_arg).is_some() || is_leaderboard {
+        return Some(first_arg);
+    }
+    let version = first_arg.trim_start_matches('#').parse::<u64>().ok()?;
+    data.submission(version).map(|submission| submission.series.clone())
+}
+
+/// Runs `authorize` with the roles of the member's guild for every series the arguments `scopes`
+/// are about, so a command that takes several versions, like `!diff`, can't be used to look at a
+/// series the member has no role for. If none of them is about a series, the capability is needed
+/// across every series.
+pub fn authorize_scopes(data: &Data, guild_id: u64, member: &Member, capability: Capability, scopes: &[&str]) -> Result<(), String> {
+    let mut series_ids: Vec<Option<String>> = scopes.iter().filter_map(|scope| series_of(data, scope)).map(Some).collect();
+    series_ids.sort();
+    series_ids.dedup();
+    if series_ids.is_empty() {
+        series_ids.push(None);
+    }
+    let unconfigured = Roles::default();
+    let roles = data.roles.get(&guild_id).unwrap_or(&unconfigured);
+    series_ids.iter().try_for_each(|series_id| authorize(roles, member, capability, series_id.as_deref()))
+}
+
+/// How many of a command's first arguments name a series or a version. Most commands are about the
+/// series named first, `!diff` compares two versions, which may be from different series.
+fn scope_count(command: &str) -> usize {
+    match command {
+        "diff" => 2,
+        _ => 1,
+    }
+}
+
+/// Runs `authorize` for the author of a `!` command.
+async fn check_message(ctx: &Context, msg: &Message, args: &Args, options: &CommandOptions, capability: Capability) -> Result<(), Reason> {
+    let (guild_id, member) = match (msg.guild_id, msg.member(ctx).await) {
+        (Some(guild_id), Ok(member)) => (*guild_id.as_u64(), Member {
+            roles: member.roles.iter().map(|role| *role.as_u64()).collect(),
+            permissions: member.permissions(ctx).unwrap_or_else(|_| Permissions::empty()),
+        }),
+        _ => return Err(Reason::User(String::from("These commands only work in servers!"))),
+    };
+    let command = options.names.first().copied().unwrap_or_default();
+    let scopes: Vec<&str> = args.raw().take(scope_count(command)).collect();
+    Store::get(ctx).await.read(|data| authorize_scopes(data, guild_id, &member, capability, &scopes)).await.map_err(Reason::User)
+}
+
+#[check]
+async fn results_editor(ctx: &Context, msg: &Message, args: &mut Args, options: &CommandOptions) -> Result<(), Reason> {
+    check_message(ctx, msg, args, options, Capability::ResultsEditor).await
+}
+
+#[check]
+async fn submissions_reviewer(ctx: &Context, msg: &Message, args: &mut Args, options: &CommandOptions) -> Result<(), Reason> {
+    check_message(ctx, msg, args, options, Capability::SubmissionsReviewer).await
+}
+
+#[check]
+async fn season_admin(ctx: &Context, msg: &Message, args: &mut Args, options: &CommandOptions) -> Result<(), Reason> {
+    check_message(ctx, msg, args, options, Capability::SeasonAdmin).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EM: u64 = 1;
+    const GT1_EM: u64 = 2;
+    const GUILD: u64 = 10;
+
+    fn member(roles: &[u64], permissions: Permissions) -> Member {
+        Member { roles: roles.to_vec(), permissions }
+    }
+
+    #[test]
+    fn falls_back_to_manage_roles_until_configured() {
+        let roles = Roles::default();
+        assert!(authorize(&roles, &member(&[], Permissions::MANAGE_ROLES), Capability::ResultsEditor, Some("gt1")).is_ok());
+        assert!(authorize(&roles, &member(&[EM], Permissions::empty()), Capability::ResultsEditor, Some("gt1")).is_err());
+        assert!(authorize(&roles, &member(&[], Permissions::ADMINISTRATOR), Capability::SeasonAdmin, None).is_ok());
+    }
+
+    #[test]
+    fn roles_per_capability_and_series() {
+        let mut roles = Roles::default();
+        roles.grant(Capability::ResultsEditor, EM, None);
+        roles.grant(Capability::SubmissionsReviewer, GT1_EM, Some("gt1"));
+
+        let em = member(&[EM], Permissions::empty());
+        let gt1_em = member(&[GT1_EM], Permissions::empty());
+        let moderator = member(&[], Permissions::MANAGE_ROLES);
+        assert!(authorize(&roles, &em, Capability::ResultsEditor, Some("group_c")).is_ok());
+        assert!(authorize(&roles, &em, Capability::ResultsEditor, None).is_ok());
+        assert!(authorize(&roles, &moderator, Capability::ResultsEditor, Some("gt1")).is_err());
+
+        assert!(authorize(&roles, &gt1_em, Capability::SubmissionsReviewer, Some("gt1")).is_ok());
+        assert!(authorize(&roles, &gt1_em, Capability::SubmissionsReviewer, Some("group_c")).is_err());
+        assert!(authorize(&roles, &gt1_em, Capability::SeasonAdmin, Some("gt1")).is_err());
+        // Nobody has been made a reviewer for Group C yet
+        assert!(authorize(&roles, &moderator, Capability::SubmissionsReviewer, Some("group_c")).is_ok());
+
+        assert!(roles.revoke(Capability::SubmissionsReviewer, GT1_EM, Some("gt1")));
+        assert!(authorize(&roles, &gt1_em, Capability::SubmissionsReviewer, Some("gt1")).is_err());
+        assert!(authorize(&roles, &moderator, Capability::SubmissionsReviewer, Some("gt1")).is_ok());
+    }
+
+    #[test]
+    fn capabilities_and_series_of_commands() {
+        assert_eq!("Season_Admin".parse::<Capability>(), Ok(Capability::SeasonAdmin));
+        assert!("admin".parse::<Capability>().is_err());
+
+        let mut data = Data::default();
+        data.leaderboards.guild(5).insert(String::from("legacy"), crate::leaderboard::Leaderboard::new(0, 0));
+        assert_eq!(series_of(&data, "GT1").as_deref(), Some("gt1"));
+        assert_eq!(series_of(&data, "legacy").as_deref(), Some("legacy"));
+        assert_eq!(series_of(&data, "#3"), None);
+        assert_eq!(series_of(&data, ""), None);
+    }
+
+    #[test]
+    fn every_series_a_command_is_about_is_checked() {
+        let mut data = Data::default();
+        for (series, hash) in [("gt1", "a"), ("group_c", "b")] {
+            data.add_submission(crate::storage::Submission {
+                id: 0,
+                series: String::from(series),
+                round: 1,
+                driver: 7,
+                entry: 1,
+                car: String::new(),
+                filename: String::from("car.zip"),
+                csv: String::new(),
+                hash: String::from(hash),
+                file: None,
+                submitted_at: 0,
+                verdict: crate::storage::Verdict::Passed,
+                restored_from: None,
+            });
+        }
+        let roles = data.roles.entry(GUILD).or_default();
+        roles.grant(Capability::SubmissionsReviewer, GT1_EM, Some("gt1"));
+        roles.grant(Capability::SubmissionsReviewer, EM, Some("group_c"));
+
+        let gt1_em = member(&[GT1_EM], Permissions::empty());
+        assert!(authorize_scopes(&data, GUILD, &gt1_em, Capability::SubmissionsReviewer, &["#1", "#1"]).is_ok());
+        // Comparing a GT1 car to a Group C one needs a role in both series
+        assert!(authorize_scopes(&data, GUILD, &gt1_em, Capability::SubmissionsReviewer, &["#1", "#2"]).is_err());
+        assert!(authorize_scopes(&data, GUILD, &member(&[GT1_EM, EM], Permissions::empty()), Capability::SubmissionsReviewer, &["#1", "#2"]).is_ok());
+        // A version that doesn't exist doesn't widen the check, the command just won't find it
+        assert!(authorize_scopes(&data, GUILD, &gt1_em, Capability::SubmissionsReviewer, &["#1", "#9"]).is_ok());
+        assert!(authorize_scopes(&data, GUILD, &gt1_em, Capability::SubmissionsReviewer, &["#9"]).is_err());
+        assert_eq!(scope_count("diff"), 2);
+        assert_eq!(scope_count("history"), 1);
+    }
+
+    #[test]
+    fn roles_are_kept_per_guild() {
+        let mut data = Data::default();
+        data.roles.entry(GUILD).or_default().grant(Capability::ResultsEditor, EM, None);
+        let moderator = member(&[], Permissions::MANAGE_ROLES);
+        assert!(authorize_scopes(&data, GUILD, &moderator, Capability::ResultsEditor, &["gt1"]).is_err());
+        // Another guild hasn't granted anything, so it's still up to whoever can manage roles there
+        assert!(authorize_scopes(&data, GUILD + 1, &moderator, Capability::ResultsEditor, &["gt1"]).is_ok());
+        assert!(authorize_scopes(&data, GUILD + 1, &member(&[EM], Permissions::empty()), Capability::ResultsEditor, &["gt1"]).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+use crate::series::{self, Series};
+
+/// Where the config is read from, unless `RNR_CONFIG` says otherwise.
+pub const DEFAULT_PATH: &str = "config.toml";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Everything that differs between the communities the bot runs in. Read from a TOML file at
+/// startup, after which any of the `RNR_*` environment variables below override the file.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The bot's Discord token. `RNR_TOKEN`.
+    pub token: String,
+    /// What `!` commands start with. `RNR_PREFIX`.
+    pub prefix: String,
+    /// Whether the `!` commands are used at all. Without them the bot gets by with slash commands
+    /// and doesn't need the privileged message content intent. `RNR_MESSAGE_COMMANDS`.
+    pub message_commands: bool,
+    /// Where the bot's data is kept. `RNR_DATA_FILE`.
+    pub data_file: PathBuf,
+    /// The leaderboard file from before `data_file`, imported the first time the bot starts
+    /// without any data. `RNR_LEGACY_LEADERBOARD`.
+    pub legacy_leaderboard: PathBuf,
+    /// Roles to get in touch with when something can't be sorted out through the bot, like a
+    /// submission that's too big. `RNR_CONTACT_ROLES`, separated by commas.
+    pub contact_roles: Vec<u64>,
+    /// The series cars can be submitted to. The MCS Season 1 series if none are given.
+    pub series: Vec<Series>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            token: String::new(),
+            prefix: String::from("!"),
+            message_commands: true,
+            data_file: PathBuf::from("data.json"),
+            legacy_leaderboard: PathBuf::from("leaderboard.json"),
+            contact_roles: Vec::new(),
+            series: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Reads the config file, which may be missing if everything is set through the environment.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let source = match std::fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+        };
+        Self::parse(&source, |name| std::env::var(name).ok()).map_err(|e| format!("Invalid config {}: {}", path.display(), e))
+    }
+
+    /// Parses a config and applies the overrides `env` returns.
+    pub fn parse<E: Fn(&str) -> Option<String>>(source: &str, env: E) -> Result<Self, String> {
+        let mut config: Config = toml::from_str(source).map_err(|e| e.to_string())?;
+        if let Some(token) = env("RNR_TOKEN") {
+            config.token = token;
+        }
+        if let Some(prefix) = env("RNR_PREFIX") {
+            config.prefix = prefix;
+        }
+        if let Some(message_commands) = env("RNR_MESSAGE_COMMANDS") {
+            config.message_commands = match message_commands.trim().to_lowercase().as_str() {
+                "true" | "1" | "yes" => true,
+                "false" | "0" | "no" => false,
+                _ => return Err(format!("RNR_MESSAGE_COMMANDS has to be true or false, not `{}`", message_commands)),
+            };
+        }
+        if let Some(data_file) = env("RNR_DATA_FILE") {
+            config.data_file = PathBuf::from(data_file);
+        }
+        if let Some(legacy_leaderboard) = env("RNR_LEGACY_LEADERBOARD") {
+            config.legacy_leaderboard = PathBuf::from(legacy_leaderboard);
+        }
+        if let Some(contact_roles) = env("RNR_CONTACT_ROLES") {
+            config.contact_roles = contact_roles.split(',').map(str::trim).filter(|role| !role.is_empty())
+                .map(|role| role.parse::<u64>().map_err(|_| format!("`{}` in RNR_CONTACT_ROLES isn't a role id", role)))
+                .collect::<Result<_, _>>()?;
+        }
+        if config.series.is_empty() {
+            config.series = series::defaults();
+        }
+
+        config.token = config.token.trim().to_string();
+        if config.token.is_empty() {
+            return Err(String::from("No bot token, set `token` or RNR_TOKEN"));
+        }
+        if config.prefix.is_empty() {
+            return Err(String::from("The prefix can't be empty"));
+        }
+        let mut ids: Vec<String> = config.series.iter().map(|series| series.id.to_lowercase()).collect();
+        ids.sort();
+        if let Some(duplicate) = ids.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(format!("There are several series with the id `{}`", duplicate[0]));
+        }
+        if let Some(series) = config.series.iter().find(|series| series.id.is_empty() || series.id.contains(char::is_whitespace)) {
+            return Err(format!("`{}` can't be used as a series id, as it has to be a single word", series.id));
+        }
+        Ok(config)
+    }
+
+    /// How replies refer to a command: with the prefix, or as a slash command when the `!`
+    /// commands are off.
+    pub fn command(&self, name: &str) -> String {
+        if self.message_commands {
+            format!("{}{}", self.prefix, name)
+        } else {
+            format!("/{}", name)
+        }
+    }
+
+    /// Who to tell people to get in touch with.
+    pub fn contact(&self) -> String {
+        if self.contact_roles.is_empty() {
+            String::from("the EMs")
+        } else {
+            self.contact_roles.iter().map(|role| format!("<@&{}>", role)).collect::<Vec<_>>().join(" or ")
+        }
+    }
+}
+
+/// Makes `config` the one the whole bot uses. Has to happen before anything reads it.
+pub fn init(config: Config) {
+    if CONFIG.set(config).is_err() {
+        panic!("The config was already set");
+    }
+}
+
+/// The bot's config. Without `init`, as in tests, it's the default config with the default series.
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(|| Config { series: series::defaults(), ..Config::default() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| vars.iter().find(|(var, _)| *var == name).map(|(_, value)| String::from(*value))
+    }
+
+    #[test]
+    fn environment_overrides_the_file() {
+        let source = r#"
+            token = "from file"
+            prefix = "?"
+            contact_roles = [1, 2]
+
+            [[series]]
+            id = "gt3"
+            name = "GT3 Cup"
+            regulations = "regulations/gt3.toml"
+            registration_dir = "registered/gt3"
+        "#;
+        let config = Config::parse(source, env(&[])).unwrap();
+        assert_eq!((config.token.as_str(), config.prefix.as_str()), ("from file", "?"));
+        assert_eq!(config.data_file, PathBuf::from("data.json"));
+        assert_eq!(config.series.len(), 1);
+        assert_eq!(config.contact(), "<@&1> or <@&2>");
+
+        let config = Config::parse(source, env(&[("RNR_TOKEN", " from env\n"), ("RNR_DATA_FILE", "/var/lib/rnr/data.json"), ("RNR_CONTACT_ROLES", "3, 4")])).unwrap();
+        assert_eq!(config.token, "from env");
+        assert_eq!(config.data_file, PathBuf::from("/var/lib/rnr/data.json"));
+        assert_eq!(config.contact_roles, vec![3, 4]);
+        assert!(Config::parse(source, env(&[("RNR_CONTACT_ROLES", "EMs")])).is_err());
+
+        assert_eq!(config.command("results"), "?results");
+        let config = Config::parse(source, env(&[("RNR_MESSAGE_COMMANDS", "false")])).unwrap();
+        assert!(!config.message_commands);
+        assert_eq!(config.command("results"), "/results");
+        assert!(Config::parse(source, env(&[("RNR_MESSAGE_COMMANDS", "off")])).is_err());
+    }
+
+    #[test]
+    fn defaults_and_validation() {
+        let config = Config::parse("", env(&[("RNR_TOKEN", "token")])).unwrap();
+        assert_eq!(config.prefix, "!");
+        assert_eq!(config.series, series::defaults());
+        assert_eq!(config.contact(), "the EMs");
+
+        assert!(Config::parse("", env(&[])).is_err());
+        assert!(Config::parse("tokn = \"typo\"", env(&[("RNR_TOKEN", "token")])).is_err());
+        let duplicate = "[[series]]\nid = \"gt1\"\nname = \"a\"\nregulations = \"a\"\nregistration_dir = \"a\"\n".repeat(2);
+        assert!(Config::parse(&duplicate, env(&[("RNR_TOKEN", "token")])).is_err());
+    }
+
+    #[test]
+    fn example_config_is_valid() {
+        let config = Config::parse(include_str!("../config.example.toml"), env(&[("RNR_TOKEN", "token")])).unwrap();
+        assert_eq!(config.series, series::defaults());
+    }
+}
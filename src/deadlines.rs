@@ -0,0 +1,186 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use serenity::http::Http;
+use serenity::model::id::ChannelId;
+
+use crate::results::valid_date;
+use crate::storage::{Data, RoundSnapshot, Store};
+
+/// How often the deadlines are checked.
+const TICK: Duration = Duration::from_secs(30);
+
+/// Something that has to be posted because a deadline is coming up or has passed.
+#[derive(Debug, PartialEq)]
+pub enum Due {
+    Reminder { series: String, round: u32, deadline: u64, channel: Option<u64> },
+    /// The deadline passed and the round has been locked.
+    Locked { series: String, snapshot: RoundSnapshot, channel: Option<u64> },
+}
+
+/// Locks every round whose deadline has passed and collects the reminders that are due. Both are
+/// marked as done in `data`, so once that's saved nothing is posted twice, even across restarts.
+/// Reminders that fall due at the same time, like after the deadline was set close to the end or
+/// the bot was down for a while, are posted as one.
+pub fn take_due(data: &mut Data, now: u64) -> Vec<Due> {
+    let mut due = Vec::new();
+    let series_ids: Vec<String> = data.series.keys().cloned().collect();
+    for series in series_ids {
+        let settings = data.series_settings(&series);
+        let deadline = match settings.deadline {
+            Some(deadline) => deadline,
+            None => continue,
+        };
+        if now >= deadline {
+            let snapshot = data.lock_round(&series, now);
+            due.push(Due::Locked { series, snapshot, channel: settings.announcement_channel });
+            continue;
+        }
+        let reminders: Vec<u64> = settings.reminders.iter()
+            .filter(|before| now + **before >= deadline && !settings.reminders_sent.contains(before))
+            .copied()
+            .collect();
+        if !reminders.is_empty() {
+            data.series.entry(series.clone()).or_default().reminders_sent.extend(reminders);
+            due.push(Due::Reminder { series, round: settings.round, deadline, channel: settings.announcement_channel });
+        }
+    }
+    due
+}
+
+/// Reads a deadline: a Unix timestamp, a Discord timestamp like `<t:1667649600:f>`, or a UTC date
+/// and time written as `YYYY-MM-DD HH:MM`.
+pub fn parse_deadline(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let timestamp = text.strip_prefix("<t:").and_then(|rest| rest.strip_suffix('>')).map(|rest| rest.split(':').next().unwrap_or_default());
+    if let Ok(seconds) = timestamp.unwrap_or(text).parse::<u64>() {
+        return Some(seconds);
+    }
+
+    let (date, time) = text.split_once(' ')?;
+    if !valid_date(date) {
+        return None;
+    }
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let (hours, minutes) = (hours.parse::<u64>().ok()?, minutes.parse::<u64>().ok()?);
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    let mut parts = date.split('-').map(|part| part.parse::<i64>().unwrap_or_default());
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    let days = u64::try_from(days_from_civil(year, month, day)).ok()?;
+    Some(days * 24 * 60 * 60 + hours * 60 * 60 + minutes * 60)
+}
+
+/// Days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Checks the deadlines in the background for as long as the bot runs. Anything that fell due
+/// while the bot was down is handled on the first check.
+pub fn spawn(store: Arc<Store>, http: Arc<Http>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TICK);
+        loop {
+            interval.tick().await;
+            let due = match store.update(|data| Ok(take_due(data, crate::submission::now()))).await {
+                Ok(due) => due.unwrap_or_default(),
+                Err(e) => {
+                    println!("Failed to check the deadlines: {:?}", e);
+                    continue;
+                },
+            };
+            for due in due {
+                announce(&http, due).await;
+            }
+        }
+    });
+}
+
+async fn announce(http: &Http, due: Due) {
+    let (series, channel, content) = match due {
+        Due::Reminder { series, round, deadline, channel } => {
+            let name = crate::series::find(&series).map_or(series.as_str(), |series| series.name.as_str());
+            let content = format!("Submissions for round {} of {} close <t:{}:R>, at <t:{}:f>!", round, name, deadline, deadline);
+            (series, channel, content)
+        },
+        Due::Locked { series, snapshot, channel } => {
+            let name = crate::series::find(&series).map_or(series.as_str(), |series| series.name.as_str());
+            let content = format!(
+                "The deadline has passed! Round {} of {} is locked with {} car(s). Submissions are closed until they're opened for round {}.",
+                snapshot.round, name, snapshot.cars.len(), snapshot.round + 1,
+            );
+            (series, channel, content)
+        },
+    };
+    match channel {
+        Some(channel) => {
+            if let Err(e) = ChannelId(channel).say(http, content).await {
+                println!("Failed to post a deadline message for {}: {:?}", series, e);
+            }
+        },
+        None => println!("{}", content),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::SeriesSettings;
+
+    const HOUR: u64 = 60 * 60;
+
+    fn with_deadline(deadline: u64) -> Data {
+        let mut data = Data::default();
+        data.series.insert(String::from("gt1"), SeriesSettings { deadline: Some(deadline), announcement_channel: Some(5), ..SeriesSettings::default() });
+        data
+    }
+
+    #[test]
+    fn reminders_are_posted_once() {
+        let deadline = 100 * HOUR;
+        let mut data = with_deadline(deadline);
+        assert!(take_due(&mut data, deadline - 30 * HOUR).is_empty());
+        let reminder = Due::Reminder { series: String::from("gt1"), round: 1, deadline, channel: Some(5) };
+        assert_eq!(take_due(&mut data, deadline - 24 * HOUR), vec![reminder]);
+        assert!(take_due(&mut data, deadline - 23 * HOUR).is_empty());
+        assert_eq!(take_due(&mut data, deadline - HOUR).len(), 1);
+        assert!(take_due(&mut data, deadline - 1).is_empty());
+
+        // Both reminders are due at once when the deadline is set with less than an hour left
+        let mut data = with_deadline(HOUR / 2);
+        assert_eq!(take_due(&mut data, 0).len(), 1);
+        assert!(take_due(&mut data, 1).is_empty());
+    }
+
+    #[test]
+    fn rounds_are_locked_after_the_deadline() {
+        let mut data = with_deadline(10);
+        match take_due(&mut data, 10).as_slice() {
+            [Due::Locked { snapshot, .. }] => assert_eq!(snapshot.round, 1),
+            due => panic!("{:?}", due),
+        }
+        let settings = data.series_settings("gt1");
+        assert_eq!((settings.round, settings.deadline, settings.submissions_open), (2, None, false));
+        assert!(take_due(&mut data, 11).is_empty());
+    }
+
+    #[test]
+    fn deadlines() {
+        assert_eq!(parse_deadline("1667649600"), Some(1667649600));
+        assert_eq!(parse_deadline("<t:1667649600:f>"), Some(1667649600));
+        assert_eq!(parse_deadline("<t:1667649600>"), Some(1667649600));
+        assert_eq!(parse_deadline("2022-11-05 12:00"), Some(1667649600));
+        assert_eq!(parse_deadline("1970-01-01 00:00"), Some(0));
+        assert_eq!(parse_deadline("2024-02-29 23:59"), Some(1709251140));
+        assert_eq!(parse_deadline("2022-11-05 24:00"), None);
+        assert_eq!(parse_deadline("2022-11-05"), None);
+        assert_eq!(parse_deadline("tomorrow"), None);
+    }
+}
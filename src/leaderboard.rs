@@ -1,36 +1,72 @@
+use serenity::framework::standard::CommandError;
 use serenity::http::Http;
 use std::collections::HashMap;
 use serde::{Serialize, Deserialize};
 
-#[derive(Serialize, Deserialize)]
+use crate::results::Event;
+use crate::scoring::{self, PointsSystem, Standing};
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Leaderboard {
     pub channel_id: u64,
     pub message_id: u64,
+    /// Wins and podiums counted by hand before results were recorded, on top of the ones from `events`.
     pub leaderboard: Option<HashMap<u64, (u32, u32)>>,
+    #[serde(default)]
+    pub points: PointsSystem,
+    #[serde(default)]
+    pub events: Vec<Event>,
 }
 
 impl Leaderboard {
-    pub fn get_sorted_results(&self) -> Vec<(u64, (u32, u32))> {
-        if let Some(lb) = &self.leaderboard {
-            let mut lb_vec: Vec<(u64,(u32, u32))> = lb.iter().map(|(k,v)| (*k,*v)).collect();
-            lb_vec.sort_by(|(_ka,va), (_kb,vb)| va.cmp(vb).reverse());
-            lb_vec
-        } else {
-            Vec::new()
+    pub fn new(channel_id: u64, message_id: u64) -> Self {
+        Self { channel_id, message_id, leaderboard: Some(HashMap::new()), points: PointsSystem::default(), events: Vec::new() }
+    }
+
+    pub fn get_sorted_results(&self) -> Vec<Standing> {
+        let standings = scoring::standings(&self.points, &self.events);
+        match &self.leaderboard {
+            Some(lb) => scoring::with_manual_counts(standings, lb),
+            None => standings,
         }
     }
 
-    pub async fn get_formatted(&self, http: &Http) -> String {
+    pub async fn get_formatted(&self, http: &Http, title: &str) -> String {
         let lb_sorted = self.get_sorted_results();
-        let mut content = String::from("```md\n === Leaderboard ===\n");
-        let mut i = 1;
-        for (id, (wins, podiums)) in lb_sorted {
+        let mut content = format!("```md\n === {} Leaderboard ===\n", title);
+        for (i, standing) in (1..).zip(lb_sorted) {
             if i > 50 { break; } // Show a maximum of top 50
-            let name = http.get_user(id).await.map(|user| format!("{}#{}", user.name, user.discriminator)).unwrap_or(format!("{}", id));
-            content.push_str(&format!("[{}][ {} wins / {} podiums - {} ]\n", i, wins, podiums, name));
-            i += 1;
+            let name = http.get_user(standing.driver).await.map(|user| format!("{}#{}", user.name, user.discriminator)).unwrap_or(format!("{}", standing.driver));
+            content.push_str(&format!("[{}][ {} pts / {} wins / {} podiums - {} ]\n", i, standing.points, standing.wins, standing.podiums, name));
         }
         content.push_str("```");
         content
     }
+
+    /// Rewrites the leaderboard post with the current standings.
+    pub async fn update_post(&self, http: &Http, title: &str) -> Result<(), CommandError> {
+        let mut lb_msg = http.get_message(self.channel_id, self.message_id).await?;
+        let content = self.get_formatted(http, title).await;
+        lb_msg.edit(http, |m| m.content(content)).await?;
+        println!("Message edited!");
+        Ok(())
+    }
+}
+
+/// Every leaderboard the bot keeps, by guild and then by series id.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Leaderboards {
+    pub guilds: HashMap<u64, HashMap<String, Leaderboard>>,
+}
+
+impl Leaderboards {
+    /// The leaderboards of a single guild, by series id.
+    pub fn guild(&mut self, guild_id: u64) -> &mut HashMap<String, Leaderboard> {
+        self.guilds.entry(guild_id).or_default()
+    }
+
+    /// A guild's leaderboard for a series, if it has one.
+    pub fn get(&self, guild_id: u64, series_id: &str) -> Option<&Leaderboard> {
+        self.guilds.get(&guild_id).and_then(|guild| guild.get(series_id))
+    }
 }
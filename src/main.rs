@@ -1,31 +1,78 @@
-use std::io::Read;
-use car_checker::regulations::Regulations;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+
+use car_checker::diff::CarDiff;
+use car_checker::regulations::rules::RuleSet;
 use serenity::async_trait;
+use serenity::builder::CreateEmbed;
 use serenity::prelude::*;
+use serenity::model::application::interaction::Interaction;
+use serenity::model::guild::Guild;
 use serenity::model::prelude::Ready;
 use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, RoleId, UserId};
 use serenity::framework::standard::macros::{command, group, hook};
-use serenity::framework::standard::{StandardFramework, CommandResult, DispatchError};
+use serenity::framework::standard::{StandardFramework, CommandError, CommandResult, DispatchError, Args, Reason};
 
+mod auth;
+mod config;
+use auth::{Capability, RESULTS_EDITOR_CHECK, SEASON_ADMIN_CHECK, SUBMISSIONS_REVIEWER_CHECK};
+mod deadlines;
 mod leaderboard;
 use leaderboard::Leaderboard;
+use results::{Event, Status};
+mod results;
+mod scoring;
+mod series;
+use series::Series;
+mod slash;
+mod storage;
+use storage::{Data, JsonFile, Store, Submission, Verdict};
+mod submission;
+mod workspace;
+use workspace::Workspace;
+
+#[group]
+#[checks(results_editor)]
+#[commands(create_leaderboard_post, add_event, set_result, edit_result, remove_win, remove_podium, results, set_scoring, refresh_leaderboard)]
+struct Results;
 
 #[group]
-#[required_permissions("MANAGE_ROLES")]
-#[commands(ping, create_leaderboard_post, add_win, remove_win, add_podium, remove_podium, refresh_leaderboard, finalize_group_c, finalize_gt1, dump_changes_group_c, dump_changes_gt1)]
-struct General;
+#[checks(submissions_reviewer)]
+#[commands(history, fetch_submission, diff, rollback, round_changes)]
+struct Review;
 
 #[group]
-#[commands(submit_group_c, submit_gt1)]
+#[checks(season_admin)]
+#[commands(open_submissions, close_submissions, set_entry_limit, lock_round, season, set_deadline, clear_deadline, set_reminders, grant_role, revoke_role, roles)]
+struct Season;
+
+#[group]
+#[commands(ping, submit, submit_group_c, submit_gt1, my_car)]
 struct Open;
 
 struct Handler;
 
 #[async_trait]
 impl EventHandler for Handler {
-    async fn ready(&self, _: Context, ready: Ready) {
+    async fn ready(&self, ctx: Context, ready: Ready) {
         println!("{} is connected!", ready.user.name);
+        for guild in &ready.guilds {
+            if let Err(e) = slash::register(&ctx, guild.id).await {
+                println!("Failed to register slash commands in {}: {:?}", guild.id, e);
+            }
+        }
+    }
+
+    async fn guild_create(&self, ctx: Context, guild: Guild, is_new: bool) {
+        if is_new {
+            if let Err(e) = slash::register(&ctx, guild.id).await {
+                println!("Failed to register slash commands in {}: {:?}", guild.id, e);
+            }
+        }
+    }
+
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        slash::handle(&ctx, interaction).await;
     }
 }
 
@@ -39,6 +86,8 @@ async fn dispatch_error(ctx: &Context, msg: &Message, error: DispatchError, _com
                 .say(&ctx.http, &format!("Try this again in {} seconds.", info.as_secs()))
                 .await;
         }
+    } else if let DispatchError::CheckFailed(_, Reason::User(reason)) = error {
+        let _ = msg.reply(ctx, reason).await;
     } else {
         let _ = msg.channel_id.say(ctx, &format!("Error occured: {:?}", error)).await;
     }
@@ -46,20 +95,42 @@ async fn dispatch_error(ctx: &Context, msg: &Message, error: DispatchError, _com
 
 #[tokio::main]
 async fn main() {
+    let config_path = std::env::var("RNR_CONFIG").unwrap_or_else(|_| String::from(config::DEFAULT_PATH));
+    config::init(config::Config::load(config_path).expect("Failed to load the config"));
+    let config = config::get();
+
     let framework = StandardFramework::new()
-        .configure(|c| c.prefix("!")) // set the bot's prefix to "!"
+        .configure(|c| c.prefix(&config.prefix))
         .on_dispatch_error(dispatch_error)
-        .group(&GENERAL_GROUP)
+        .group(&RESULTS_GROUP)
+        .group(&REVIEW_GROUP)
+        .group(&SEASON_GROUP)
         .group(&OPEN_GROUP);
 
-    // Login with a bot token from the environment
-    let token = include_str!("../token.txt").trim();
-    let intents = GatewayIntents::non_privileged() | GatewayIntents::MESSAGE_CONTENT;
-    let mut client = Client::builder(token, intents)
-        .event_handler(Handler)
-        .framework(framework)
-        .await
-        .expect("Error creating client");
+    // Login with the bot token from the config or the environment
+    let token = &config.token;
+    // Slash commands work without MESSAGE_CONTENT, it's only needed for the `!` commands
+    let mut intents = GatewayIntents::non_privileged();
+    if config.message_commands {
+        intents |= GatewayIntents::MESSAGE_CONTENT;
+    }
+    let mut builder = Client::builder(token, intents).event_handler(Handler);
+    if config.message_commands {
+        builder = builder.framework(framework);
+    }
+    let mut client = builder.await.expect("Error creating client");
+
+    let store = Store::open(Box::new(JsonFile::new(&config.data_file)), &config.legacy_leaderboard, &client.cache_and_http.http).await.expect("Failed to open the bot's data");
+    for series in series::all() {
+        match workspace::sweep(&series.registration_dir) {
+            Ok(0) => {},
+            Ok(swept) => println!("Removed {} workspace(s) left behind in {}", swept, series.registration_dir),
+            Err(e) => println!("Failed to clean up {}: {:?}", series.registration_dir, e),
+        }
+    }
+    let store = std::sync::Arc::new(store);
+    client.data.write().await.insert::<Store>(store.clone());
+    deadlines::spawn(store, client.cache_and_http.http.clone());
 
     // start listening for events by starting a single shard
     if let Err(why) = client.start().await {
@@ -73,528 +144,986 @@ async fn ping(ctx: &Context, msg: &Message) -> CommandResult {
     Ok(())
 }
 
-#[command]
-async fn create_leaderboard_post(ctx: &Context, msg: &Message) -> CommandResult {
-    let msg = msg.channel_id.say(ctx, "Leaderboard placeholder message!").await?;
-    println!("Loading leaderboard...");
-    let mut leaderboard: Leaderboard = serde_json::from_str(&std::fs::read_to_string("leaderboard.json")?)?;
-    leaderboard.channel_id = *msg.channel_id.as_u64();
-    leaderboard.message_id = *msg.id.as_u64();
-    // Shitty hack
-    if leaderboard.leaderboard.is_none() {
-        leaderboard.leaderboard = Some(HashMap::new());
-    }
-    println!("Leaderboard updated...");
-    std::fs::write("leaderboard.json", serde_json::to_string(&leaderboard)?)?;
-    println!("Made leaderboard post!");
+/// The title shown on a series' leaderboard post.
+fn series_title(series_id: &str) -> String {
+    series::find(series_id).map(|series| series.name.clone()).unwrap_or_else(|| String::from(series_id))
+}
+
+/// Reads the `<series>` argument of a leaderboard command, returning the guild and series id or
+/// what to reply with instead.
+fn series_arg(msg: &Message, args: &mut Args, data: &Data, must_exist: bool) -> Result<(u64, String), String> {
+    let series_id = args.single::<String>().unwrap_or_default();
+    leaderboard_of(data, msg.guild_id.map(|guild_id| *guild_id.as_u64()), &series_id, must_exist)
+}
+
+/// Checks the series a leaderboard command is about, returning the guild and series id or what to
+/// reply with instead. Old leaderboards can still be named even if their series isn't in the series
+/// table.
+fn leaderboard_of(data: &Data, guild_id: Option<u64>, series_id: &str, must_exist: bool) -> Result<(u64, String), String> {
+    let guild_id = guild_id.ok_or("Leaderboards only exist in servers!")?;
+    let series_id = series_id.trim().to_lowercase();
+    let exists = data.leaderboards.get(guild_id, &series_id).is_some();
+    if series::find(&series_id).is_none() && !exists {
+        return Err(format!("Which series? Pick one of {}.", series::ids()));
+    }
+    if must_exist && !exists {
+        return Err(format!("There's no leaderboard for `{}` yet! Make one with `{} {}`", series_id, config::get().command("create_leaderboard_post"), series_id));
+    }
+    Ok((guild_id, series_id))
+}
+
+/// Rewrites a series' leaderboard post with the stored standings.
+async fn refresh_post(ctx: &Context, store: &Store, guild_id: u64, series_id: &str) -> CommandResult {
+    if let Some(leaderboard) = store.read(|data| data.leaderboards.get(guild_id, series_id).cloned()).await {
+        leaderboard.update_post(&ctx.http, &series_title(series_id)).await?;
+    }
     Ok(())
 }
 
 #[command]
-async fn add_win(ctx: &Context, msg: &Message) -> CommandResult {
-    let players_won = &msg.mentions;
-    println!("Loading leaderboard...");
-    let mut leaderboard: Leaderboard = serde_json::from_str(&std::fs::read_to_string("leaderboard.json")?)?;
-    if leaderboard.leaderboard.is_none() {
-        leaderboard.leaderboard = Some(HashMap::new());
-    }
-    if let Some(ref mut lb) = leaderboard.leaderboard {
-        for player in players_won {
-            if let Some((wins, _podiums)) = lb.get_mut(player.id.as_u64()) {
-                *wins += 1;
-            } else {
-                lb.insert(*player.id.as_u64(), (1, 0));
-            }
-        }
+async fn create_leaderboard_post(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let store = Store::get(ctx).await;
+    let (guild_id, series_id) = match store.read(|data| series_arg(msg, &mut args, data, false)).await {
+        Ok(found) => found,
+        Err(reply) => {
+            msg.reply(ctx, reply).await?;
+            return Ok(());
+        },
+    };
+    post_leaderboard(ctx, guild_id, &series_id, msg.channel_id).await?;
+    Ok(())
+}
+
+/// Posts and pins a series' leaderboard in `channel`, where it's kept up to date from then on.
+async fn post_leaderboard(ctx: &Context, guild_id: u64, series_id: &str, channel: ChannelId) -> CommandResult {
+    let store = Store::get(ctx).await;
+    let post = channel.say(ctx, format!("{} leaderboard placeholder message!", series_title(series_id))).await?;
+    if let Err(e) = post.pin(ctx).await {
+        println!("Failed to pin leaderboard post: {:?}", e);
     }
-    println!("Leaderboard updated...");
-    std::fs::write("leaderboard.json", serde_json::to_string(&leaderboard)?)?;
+
+    store.update(|data| {
+        // Keep the standings if the series already had a post somewhere else
+        let leaderboard = data.leaderboards.guild(guild_id).entry(String::from(series_id)).or_insert_with(|| Leaderboard::new(0, 0));
+        leaderboard.channel_id = *post.channel_id.as_u64();
+        leaderboard.message_id = *post.id.as_u64();
+        Ok(())
+    }).await??;
+    refresh_post(ctx, &store, guild_id, series_id).await?;
     println!("Made leaderboard post!");
+    Ok(())
+}
+
+/// Reads `<series> <event id>` and runs `edit` on that event. `edit` returns the reply, or an error
+/// to reply with instead of saving.
+async fn edit_event<F: FnOnce(&mut Event, &mut Args) -> Result<String, String>>(ctx: &Context, msg: &Message, mut args: Args, edit: F) -> CommandResult {
+    let store = Store::get(ctx).await;
+    let (guild_id, series_id) = match store.read(|data| series_arg(msg, &mut args, data, true)).await {
+        Ok(found) => found,
+        Err(reply) => {
+            msg.reply(ctx, reply).await?;
+            return Ok(());
+        },
+    };
+    let event_id = args.single::<String>().unwrap_or_default();
+    let reply = update_event(ctx, guild_id, &series_id, &event_id, |event| edit(event, &mut args)).await?;
+    msg.reply(ctx, reply).await?;
+    Ok(())
+}
+
+/// Runs `edit` on an event and refreshes the leaderboard post if it worked, returning the reply.
+async fn update_event<F: FnOnce(&mut Event) -> Result<String, String>>(ctx: &Context, guild_id: u64, series_id: &str, event_id: &str, edit: F) -> Result<String, CommandError> {
+    let store = Store::get(ctx).await;
+    let outcome = store.update(|data| {
+        let leaderboard = data.leaderboards.guild(guild_id).get_mut(series_id).ok_or_else(|| format!("There's no leaderboard for `{}`!", series_id))?;
+        let event = leaderboard.events.iter_mut().find(|event| event.id == event_id)
+            .ok_or_else(|| format!("There's no event `{}` in `{}`! See `{} {}` for the list.", event_id, series_id, config::get().command("results"), series_id))?;
+        edit(event)
+    }).await?;
+    match outcome {
+        Ok(reply) => {
+            refresh_post(ctx, &store, guild_id, series_id).await?;
+            Ok(reply)
+        },
+        Err(reply) => Ok(reply),
+    }
+}
 
-    // Update leaderboard post
-    let lb_channel_id = leaderboard.channel_id;
-    let lb_post_id = leaderboard.message_id;
-    let mut lb_msg = ctx.http.get_message(lb_channel_id, lb_post_id).await?;
-    let content = leaderboard.get_formatted(&ctx.http).await;
-    lb_msg.edit(ctx, |m| m.content(content)).await?;
-    println!("Message edited!");
+/// Reads every remaining argument as a driver mention.
+fn drivers(args: &mut Args) -> Result<Vec<u64>, String> {
+    let mut drivers = Vec::new();
+    while !args.is_empty() {
+        let arg = args.single::<String>().unwrap_or_default();
+        let driver = arg.parse::<UserId>().map_err(|_| format!("`{}` isn't a driver, mention them instead!", arg))?;
+        drivers.push(*driver.as_u64());
+    }
+    Ok(drivers)
+}
 
+/// `!add_event <series> <event id> <YYYY-MM-DD> <track>`
+#[command]
+async fn add_event(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let store = Store::get(ctx).await;
+    let (guild_id, series_id) = match store.read(|data| series_arg(msg, &mut args, data, true)).await {
+        Ok(found) => found,
+        Err(reply) => {
+            msg.reply(ctx, reply).await?;
+            return Ok(());
+        },
+    };
+    let event_id = args.single::<String>().unwrap_or_default();
+    let date = args.single::<String>().unwrap_or_default();
+    let track = args.rest().trim().trim_matches('"').to_string();
+    if event_id.is_empty() || !results::valid_date(&date) || track.is_empty() {
+        msg.reply(ctx, format!("Usage: `{} <series> <event id> <YYYY-MM-DD> <track>`", config::get().command("add_event"))).await?;
+        return Ok(());
+    }
+    let (Ok(reply) | Err(reply)) = store.update(|data| new_event(data, guild_id, &series_id, &event_id, &date, &track)).await?;
+    msg.reply(ctx, reply).await?;
     Ok(())
 }
 
+/// Adds an event to a series' leaderboard.
+fn new_event(data: &mut Data, guild_id: u64, series_id: &str, event_id: &str, date: &str, track: &str) -> Result<String, String> {
+    let leaderboard = data.leaderboards.guild(guild_id).get_mut(series_id).ok_or("That leaderboard was just deleted!")?;
+    if leaderboard.events.iter().any(|event| event.id == event_id) {
+        return Err(format!("There already is an event `{}` in `{}`!", event_id, series_id));
+    }
+    leaderboard.events.push(Event::new(event_id, date, track, series_id));
+    Ok(format!("Added event `{}` at {} on {}. Enter its results with `{} {} {} @first @second ...`", event_id, track, date, config::get().command("set_result"), series_id, event_id))
+}
+
+/// `!set_result <series> <event id> @first @second ... [dnf @driver ...] [dsq @driver ...] [pole @driver] [fl @driver]`
 #[command]
-async fn remove_win(ctx: &Context, msg: &Message) -> CommandResult {
-    let players_corrected = &msg.mentions;
-    println!("Loading leaderboard...");
-    let mut leaderboard: Leaderboard = serde_json::from_str(&std::fs::read_to_string("leaderboard.json")?)?;
-    if leaderboard.leaderboard.is_none() {
-        leaderboard.leaderboard = Some(HashMap::new());
-    }
-    if let Some(ref mut lb) = leaderboard.leaderboard {
-        for player in players_corrected {
-            if let Some((wins, _podiums)) = lb.get_mut(player.id.as_u64()) {
-                if *wins > 0 { *wins -= 1; }
-            }
+async fn set_result(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    edit_event(ctx, msg, args, |event, args| apply_results(event, parse_results(args)?)).await
+}
+
+/// An event's results as they're entered.
+#[derive(Debug, Default, PartialEq)]
+struct Classification {
+    finished: Vec<u64>,
+    dnf: Vec<u64>,
+    dsq: Vec<u64>,
+    pole: Option<u64>,
+    fastest_lap: Option<u64>,
+}
+
+/// Reads `@first @second ... [dnf @driver ...] [dsq @driver ...] [pole @driver] [fl @driver]`.
+fn parse_results(args: &mut Args) -> Result<Classification, String> {
+    let mut classification = Classification::default();
+    let mut group = Status::Finished;
+    while !args.is_empty() {
+        let arg = args.single::<String>().unwrap_or_default();
+        match arg.to_lowercase().as_str() {
+            "dnf" => group = Status::Dnf,
+            "dsq" => group = Status::Dsq,
+            keyword @ ("pole" | "fl" | "fastest_lap") => {
+                let driver = args.single::<UserId>().map_err(|_| format!("Mention who got the {} after `{}`", if keyword == "pole" { "pole" } else { "fastest lap" }, keyword))?;
+                if keyword == "pole" {
+                    classification.pole = Some(*driver.as_u64());
+                } else {
+                    classification.fastest_lap = Some(*driver.as_u64());
+                }
+            },
+            _ => {
+                let driver = *arg.parse::<UserId>().map_err(|_| format!("`{}` isn't a driver, mention them instead!", arg))?.as_u64();
+                match group {
+                    Status::Finished => classification.finished.push(driver),
+                    Status::Dnf => classification.dnf.push(driver),
+                    Status::Dsq => classification.dsq.push(driver),
+                }
+            },
         }
     }
-    println!("Leaderboard updated...");
-    std::fs::write("leaderboard.json", serde_json::to_string(&leaderboard)?)?;
-    println!("Made leaderboard post!");
+    Ok(classification)
+}
 
-    // Update leaderboard post
-    let lb_channel_id = leaderboard.channel_id;
-    let lb_post_id = leaderboard.message_id;
-    let mut lb_msg = ctx.http.get_message(lb_channel_id, lb_post_id).await?;
-    let content = leaderboard.get_formatted(&ctx.http).await;
-    lb_msg.edit(ctx, |m| m.content(content)).await?;
-    println!("Message edited!");
-    Ok(())
+/// Replaces an event's results.
+fn apply_results(event: &mut Event, classification: Classification) -> Result<String, String> {
+    if classification.finished.is_empty() && classification.dnf.is_empty() && classification.dsq.is_empty() {
+        return Err(String::from("Mention the drivers in finishing order!"));
+    }
+    event.set_results(&classification.finished, &classification.dnf, &classification.dsq);
+    event.pole = classification.pole;
+    event.fastest_lap = classification.fastest_lap;
+    Ok(format!("Saved the results of `{}`!", event.id))
 }
 
+/// `!edit_result <series> <event id> @driver <position|dnf|dsq|remove>`
 #[command]
-async fn add_podium(ctx: &Context, msg: &Message) -> CommandResult {
-    let players_won = &msg.mentions;
-    println!("Loading leaderboard...");
-    let mut leaderboard: Leaderboard = serde_json::from_str(&std::fs::read_to_string("leaderboard.json")?)?;
-    if leaderboard.leaderboard.is_none() {
-        leaderboard.leaderboard = Some(HashMap::new());
-    }
-    if let Some(ref mut lb) = leaderboard.leaderboard {
-        for player in players_won {
-            if let Some((_wins, podiums)) = lb.get_mut(player.id.as_u64()) {
-                *podiums += 1;
-            } else {
-                lb.insert(*player.id.as_u64(), (0, 1));
+async fn edit_result(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    edit_event(ctx, msg, args, |event, args| {
+        let usage = || format!("Usage: `{} <series> <event id> @driver <position|dnf|dsq|remove>`", config::get().command("edit_result"));
+        let driver = *args.single::<UserId>().map_err(|_| usage())?.as_u64();
+        let change = args.single::<String>().map_err(|_| usage())?;
+        place_driver(event, driver, &change)
+    }).await
+}
+
+/// Moves a driver to a position, `dnf` or `dsq` in an event's results, or `remove`s them.
+fn place_driver(event: &mut Event, driver: u64, change: &str) -> Result<String, String> {
+    match change.trim().to_lowercase().as_str() {
+        "dnf" => event.place(driver, Status::Dnf, None),
+        "dsq" => event.place(driver, Status::Dsq, None),
+        "remove" => {
+            if !event.remove(driver) {
+                return Err(format!("<@{}> isn't in the results of `{}`!", driver, event.id));
             }
+        },
+        position => {
+            let position = position.trim_start_matches('p').parse::<usize>().ok().filter(|position| *position > 0)
+                .ok_or_else(|| format!("`{}` isn't a position, `dnf`, `dsq` or `remove`!", change))?;
+            event.place(driver, Status::Finished, Some(position));
+        },
+    }
+    Ok(format!("Updated the results of `{}`!", event.id))
+}
+
+/// Moves drivers who finished in the top `positions` to just behind them, as for a penalty that
+/// costs them the win or the podium.
+fn remove_top_finishers(event: &mut Event, drivers: &[u64], positions: usize, what: &str) -> Result<String, String> {
+    if drivers.is_empty() {
+        return Err(format!("Mention whose {} to remove!", what));
+    }
+    for driver in drivers {
+        if event.position(*driver).is_none_or(|position| position > positions) {
+            return Err(format!("<@{}> doesn't have a {} in `{}`!", driver, what, event.id));
         }
     }
-    println!("Leaderboard updated...");
-    std::fs::write("leaderboard.json", serde_json::to_string(&leaderboard)?)?;
-    println!("Made leaderboard post!");
+    event.demote(drivers, positions);
+    if let Some(driver) = drivers.iter().find(|driver| event.position(**driver).is_some_and(|position| position <= positions)) {
+        return Err(format!("Nobody finished behind <@{}> in `{}` to take their place!", driver, event.id));
+    }
+    let moved = drivers.iter().map(|driver| format!("<@{}> to P{}", driver, event.position(*driver).unwrap_or_default())).collect::<Vec<_>>().join(", ");
+    Ok(format!("Moved {} in `{}`. Use `{}` to change it further.", moved, event.id, config::get().command("edit_result")))
+}
+
+/// `!remove_win <series> <event id> @driver ...`
+#[command]
+async fn remove_win(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    edit_event(ctx, msg, args, |event, args| remove_top_finishers(event, &drivers(args)?, 1, "win")).await
+}
+
+/// `!remove_podium <series> <event id> @driver ...`
+#[command]
+async fn remove_podium(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    edit_event(ctx, msg, args, |event, args| remove_top_finishers(event, &drivers(args)?, 3, "podium")).await
+}
 
-    // Update leaderboard post
-    let lb_channel_id = leaderboard.channel_id;
-    let lb_post_id = leaderboard.message_id;
-    let mut lb_msg = ctx.http.get_message(lb_channel_id, lb_post_id).await?;
-    let content = leaderboard.get_formatted(&ctx.http).await;
-    lb_msg.edit(ctx, |m| m.content(content)).await?;
-    println!("Message edited!");
+/// `!results <series> [event id]`
+#[command]
+async fn results(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let content = Store::get(ctx).await.read(|data| {
+        let (guild_id, series_id) = series_arg(msg, &mut args, data, true)?;
+        Ok(results_of(data, guild_id, &series_id, args.rest().trim()))
+    }).await;
+    let (Ok(content) | Err(content)) = content;
+    // Don't ping everyone in the results
+    msg.channel_id.send_message(ctx, |m| m.content(content).allowed_mentions(|mentions| mentions.empty_users())).await?;
+    Ok(())
+}
+
+/// Lists the events of a series' leaderboard, or the results of one of them if `event_id` isn't
+/// empty.
+fn results_of(data: &Data, guild_id: u64, series_id: &str, event_id: &str) -> String {
+    let leaderboard = match data.leaderboards.get(guild_id, series_id) {
+        Some(leaderboard) => leaderboard,
+        None => return format!("There's no leaderboard for `{}`!", series_id),
+    };
+    let mut content = String::new();
+    if event_id.is_empty() {
+        for event in &leaderboard.events {
+            content.push_str(&format!("`{}` {} - {} ({} entries)\n", event.id, event.date, event.track, event.finishes.len()));
+        }
+        if content.is_empty() {
+            content = format!("No events yet! Add one with `{} {} <event id> <YYYY-MM-DD> <track>`", config::get().command("add_event"), series_id);
+        }
+        return content;
+    }
+    let event = match leaderboard.events.iter().find(|event| event.id == event_id) {
+        Some(event) => event,
+        None => return format!("There's no event `{}` in `{}`!", event_id, series_id),
+    };
+    content.push_str(&format!("**{}** - {} on {}\n", event.id, event.track, event.date));
+    for finish in &event.finishes {
+        let place = match finish.status {
+            Status::Finished => format!("P{}", event.position(finish.driver).unwrap_or_default()),
+            Status::Dnf => String::from("DNF"),
+            Status::Dsq => String::from("DSQ"),
+        };
+        let points = leaderboard.points.round_points(event, finish.driver);
+        content.push_str(&format!("{} <@{}> ({} pts)\n", place, finish.driver, points));
+    }
+    if let Some(driver) = event.pole {
+        content.push_str(&format!("Pole: <@{}>\n", driver));
+    }
+    if let Some(driver) = event.fastest_lap {
+        content.push_str(&format!("Fastest lap: <@{}>\n", driver));
+    }
+    content
+}
 
+/// `!set_scoring <series> [f1] [positions=25,18,...] [pole=N] [fl=N] [drop=N]`
+#[command]
+async fn set_scoring(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let store = Store::get(ctx).await;
+    let (guild_id, series_id) = match store.read(|data| series_arg(msg, &mut args, data, true)).await {
+        Ok(found) => found,
+        Err(reply) => {
+            msg.reply(ctx, reply).await?;
+            return Ok(());
+        },
+    };
+    let settings: Vec<String> = args.iter::<String>().filter_map(Result::ok).collect();
+    let reply = change_scoring(ctx, guild_id, &series_id, &settings).await?;
+    msg.reply(ctx, reply).await?;
     Ok(())
 }
 
+/// Applies `settings` like `f1` or `pole=1` to a series' points and refreshes its post.
+async fn change_scoring(ctx: &Context, guild_id: u64, series_id: &str, settings: &[String]) -> Result<String, CommandError> {
+    let store = Store::get(ctx).await;
+    let outcome = store.update(|data| {
+        let leaderboard = data.leaderboards.guild(guild_id).get_mut(series_id).ok_or("That leaderboard was just deleted!")?;
+        for setting in settings {
+            leaderboard.points.apply(setting)?;
+        }
+        let points = &leaderboard.points;
+        Ok(format!("Points: {:?}, pole: {}, fastest lap: {}, dropped rounds: {}", points.positions, points.pole, points.fastest_lap, points.dropped_rounds))
+    }).await?;
+    if outcome.is_ok() {
+        refresh_post(ctx, &store, guild_id, series_id).await?;
+    }
+    let (Ok(reply) | Err(reply)) = outcome;
+    Ok(reply)
+}
+
 #[command]
-async fn remove_podium(ctx: &Context, msg: &Message) -> CommandResult {
-    let players_corrected = &msg.mentions;
-    println!("Loading leaderboard...");
-    let mut leaderboard: Leaderboard = serde_json::from_str(&std::fs::read_to_string("leaderboard.json")?)?;
-    if leaderboard.leaderboard.is_none() {
-        leaderboard.leaderboard = Some(HashMap::new());
-    }
-    if let Some(ref mut lb) = leaderboard.leaderboard {
-        for player in players_corrected {
-            if let Some((_wins, podiums)) = lb.get_mut(player.id.as_u64()) {
-                if *podiums > 0 { *podiums -= 1; }
-            }
+async fn refresh_leaderboard(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    if let Some(guild_id) = msg.guild_id {
+        refresh_posts(ctx, *guild_id.as_u64(), args.rest()).await?;
+    }
+    Ok(())
+}
+
+/// Refreshes the leaderboard post of a single series, or of every series in the server if
+/// `series_id` is empty, returning how many there were.
+async fn refresh_posts(ctx: &Context, guild_id: u64, series_id: &str) -> Result<usize, CommandError> {
+    let store = Store::get(ctx).await;
+    let series_id = series_id.trim().to_lowercase();
+    let series_ids: Vec<String> = store.read(|data| data.leaderboards.guilds.get(&guild_id).map(|guild| guild.keys().cloned().collect()).unwrap_or_default()).await;
+    let mut refreshed = 0;
+    for id in series_ids {
+        if series_id.is_empty() || id == series_id {
+            refresh_post(ctx, &store, guild_id, &id).await?;
+            refreshed += 1;
         }
     }
-    println!("Leaderboard updated...");
-    std::fs::write("leaderboard.json", serde_json::to_string(&leaderboard)?)?;
-    println!("Made leaderboard post!");
+    Ok(refreshed)
+}
 
-    // Update leaderboard post
-    let lb_channel_id = leaderboard.channel_id;
-    let lb_post_id = leaderboard.message_id;
-    let mut lb_msg = ctx.http.get_message(lb_channel_id, lb_post_id).await?;
-    let content = leaderboard.get_formatted(&ctx.http).await;
-    lb_msg.edit(ctx, |m| m.content(content)).await?;
-    println!("Message edited!");
+/// `!close_submissions <series>` and `!open_submissions <series>`
+async fn set_submissions_open(ctx: &Context, msg: &Message, args: Args, open: bool) -> CommandResult {
+    let series = match series::find(args.rest()) {
+        Some(series) => series,
+        None => {
+            msg.reply(ctx, format!("Which series? Pass one of {}", series::ids())).await?;
+            return Ok(());
+        },
+    };
+    let reply = Store::get(ctx).await.update(|data| Ok(set_series_open(data, series, open))).await??;
+    msg.reply(ctx, reply).await?;
     Ok(())
 }
 
+fn set_series_open(data: &mut Data, series: &Series, open: bool) -> String {
+    let settings = data.series.entry(series.id.clone()).or_default();
+    settings.submissions_open = open;
+    format!("Submissions for round {} of {} are now {}!", settings.round, series.name, if open { "open" } else { "closed" })
+}
+
+#[command]
+async fn open_submissions(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    set_submissions_open(ctx, msg, args, true).await
+}
+
+#[command]
+async fn close_submissions(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    set_submissions_open(ctx, msg, args, false).await
+}
+
+/// `!lock_round <series>`: fixes the cars of the current round and moves on to the next one.
+/// Submissions stay closed until they're opened for the new round.
 #[command]
-async fn refresh_leaderboard(ctx: &Context, _msg: &Message) -> CommandResult {
-    let leaderboard: Leaderboard = serde_json::from_str(&std::fs::read_to_string("leaderboard.json")?)?;
-    let lb_channel_id = leaderboard.channel_id;
-    let lb_post_id = leaderboard.message_id;
-    let mut lb_msg = ctx.http.get_message(lb_channel_id, lb_post_id).await?;
-    let content = leaderboard.get_formatted(&ctx.http).await;
-    lb_msg.edit(ctx, |m| m.content(content)).await?;
-    println!("Message edited!");
+async fn lock_round(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let series = match series::find(args.rest()) {
+        Some(series) => series,
+        None => {
+            msg.reply(ctx, format!("Which series? Pass one of {}", series::ids())).await?;
+            return Ok(());
+        },
+    };
+    let reply = Store::get(ctx).await.update(|data| Ok(lock_series_round(data, series, submission::now()))).await??;
+    msg.reply(ctx, reply).await?;
     Ok(())
 }
 
+fn lock_series_round(data: &mut Data, series: &Series, now: u64) -> String {
+    let snapshot = data.lock_round(&series.id, now);
+    format!(
+        "Round {} of {} is locked with {} car(s). Submissions now count for round {}, use `{} {}` to open them.",
+        snapshot.round, series.name, snapshot.cars.len(), snapshot.round + 1, config::get().command("open_submissions"), series.id,
+    )
+}
+
+/// `!set_deadline <series> <YYYY-MM-DD HH:MM, in UTC, or a timestamp>`: reminders and the lock
+/// when it passes are posted in the channel this is used in.
 #[command]
-async fn finalize_group_c(ctx: &Context, msg: &Message) -> CommandResult {
-    std::fs::rename("registered/group_c", "registered/group_c_prev")?;
-    std::fs::create_dir("registered/group_c")?;
-    msg.reply(ctx, "Submissions finalized!").await?;
+async fn set_deadline(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let series = series::find(&args.single::<String>().unwrap_or_default());
+    let (series, deadline) = match (series, deadlines::parse_deadline(args.rest())) {
+        (Some(series), Some(deadline)) => (series, deadline),
+        _ => {
+            msg.reply(ctx, format!("Usage: `{} <series> <YYYY-MM-DD HH:MM>`, in UTC, with one of {}", config::get().command("set_deadline"), series::ids())).await?;
+            return Ok(());
+        },
+    };
+    let (Ok(reply) | Err(reply)) = Store::get(ctx).await.update(|data| set_series_deadline(data, series, deadline, *msg.channel_id.as_u64(), submission::now())).await?;
+    msg.reply(ctx, reply).await?;
     Ok(())
 }
 
+/// Sets the deadline of a series' current round, announcing reminders and the lock in `channel`.
+fn set_series_deadline(data: &mut Data, series: &Series, deadline: u64, channel: u64, now: u64) -> Result<String, String> {
+    if deadline <= now {
+        return Err(String::from("That deadline has already passed!"));
+    }
+    let settings = data.series.entry(series.id.clone()).or_default();
+    settings.deadline = Some(deadline);
+    settings.reminders_sent.clear();
+    settings.announcement_channel = Some(channel);
+    Ok(format!("Submissions for round {} of {} close <t:{}:F>, <t:{}:R>. The round is locked automatically then.", settings.round, series.name, deadline, deadline))
+}
+
+/// `!clear_deadline <series>`
 #[command]
-async fn finalize_gt1(ctx: &Context, msg: &Message) -> CommandResult {
-    std::fs::rename("registered/gt1", "registered/gt1_prev")?;
-    std::fs::create_dir("registered/gt1")?;
-    msg.reply(ctx, "Submissions finalized!").await?;
+async fn clear_deadline(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let series = match series::find(args.rest()) {
+        Some(series) => series,
+        None => {
+            msg.reply(ctx, format!("Which series? Pass one of {}", series::ids())).await?;
+            return Ok(());
+        },
+    };
+    let reply = Store::get(ctx).await.update(|data| Ok(clear_series_deadline(data, series))).await??;
+    msg.reply(ctx, reply).await?;
     Ok(())
 }
 
+fn clear_series_deadline(data: &mut Data, series: &Series) -> String {
+    data.series.entry(series.id.clone()).or_default().deadline = None;
+    format!("{} no longer has a deadline, lock the round with `{} {}`.", series.name, config::get().command("lock_round"), series.id)
+}
+
+/// `!set_reminders <series> [hours before the deadline...]`, e.g. `!set_reminders gt1 24 1`.
+/// Without any hours no reminders are posted.
 #[command]
-async fn dump_changes_group_c(ctx: &Context, msg: &Message) -> CommandResult {
-    if std::path::Path::new("registered/group_c_prev").exists() == false {
-        msg.reply(ctx, "There have not been previous group c submissions!").await?;
+async fn set_reminders(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let series = series::find(&args.single::<String>().unwrap_or_default());
+    let hours: Result<Vec<u64>, _> = args.iter::<u64>().collect();
+    let (series, hours) = match (series, hours) {
+        (Some(series), Ok(hours)) => (series, hours),
+        _ => {
+            msg.reply(ctx, format!("Usage: `{} <series> [hours before the deadline...]`, with one of {}", config::get().command("set_reminders"), series::ids())).await?;
+            return Ok(());
+        },
+    };
+    let reply = Store::get(ctx).await.update(|data| Ok(set_series_reminders(data, series, &hours))).await??;
+    msg.reply(ctx, reply).await?;
+    Ok(())
+}
+
+/// Posts reminders the given numbers of hours before a series' deadlines.
+fn set_series_reminders(data: &mut Data, series: &Series, hours: &[u64]) -> String {
+    let mut reminders: Vec<u64> = hours.iter().map(|hours| hours * 60 * 60).collect();
+    reminders.sort_unstable_by(|a, b| b.cmp(a));
+    reminders.dedup();
+    let content = if reminders.is_empty() {
+        format!("No more reminders will be posted for {}.", series.name)
     } else {
-        let mut skipped_cars = Vec::new();
-        for entry in std::fs::read_dir("registered/group_c_prev")? {
-            let entry = entry?;
-            let path = entry.path();
-            if !path.is_dir() && path.extension().map(|os_str| os_str.to_str().unwrap_or("AUGH")) == Some("zip") {
-                // Found a zip file
-                // Extracting the old car
-                let mut file = std::fs::File::open(path.clone())?;
-                let mut zip_bytes = Vec::new();
-                file.read_to_end(&mut zip_bytes)?;
-                let mut zip_reader = zip::ZipArchive::new(std::io::Cursor::new(zip_bytes.clone()))?;
-                zip_reader.extract("csv/tmp_old")?;
-
-                let car_name = path.file_name().map(|s| s.to_str().unwrap_or("AUGH")).unwrap_or("AUGH");
-
-                // Check if new car exists
-                if std::path::Path::new(&format!("registered/group_c/{}", car_name)).exists() == false {
-                    skipped_cars.push(car_name.to_string());
-                    continue;
-                }
+        let hours: Vec<String> = reminders.iter().map(|seconds| format!("{}h", seconds / 60 / 60)).collect();
+        format!("Reminders for {} are posted {} before the deadline.", series.name, hours.join(", "))
+    };
+    data.series.entry(series.id.clone()).or_default().reminders = reminders;
+    content
+}
 
-                // Extracting the new car
-                let mut file = std::fs::File::open(&format!("registered/group_c/{}", car_name))?;
-                let mut zip_bytes = Vec::new();
-                file.read_to_end(&mut zip_bytes)?;
-                let mut zip_reader = zip::ZipArchive::new(std::io::Cursor::new(zip_bytes.clone()))?;
-                zip_reader.extract("csv/tmp")?;
-
-                // Find CSV files
-                let mut csv_path_old = None;
-                'search_old: for entry in std::fs::read_dir("csv/tmp_old")? {
-                    let entry = entry?;
-                    let path = entry.path();
-                    if path.is_dir() {
-                        // CSV file might be in this folder
-                        for entry in std::fs::read_dir(path)? {
-                            let entry = entry?;
-                            let path = entry.path();
-                            if path.extension().map(|os_str| os_str.to_str().unwrap_or("")) == Some("csv") {
-                                println!("Located csv file!");
-                                csv_path_old = Some(path);
-                                break 'search_old;
-                            }
-                        }
-                    } else {
-                        // CSV file is directly in ZIP file
-                        if path.extension().map(|os_str| os_str.to_str().unwrap_or("WAH")) == Some("csv") {
-                            println!("Located csv file!");
-                            csv_path_old = Some(path);
-                            break 'search_old;
-                        }
-                    }
-                }
-                let csv_path_old = csv_path_old.ok_or(std::fmt::Error)?;
-
-                let mut csv_path = None;
-                'search: for entry in std::fs::read_dir("csv/tmp")? {
-                    let entry = entry?;
-                    let path = entry.path();
-                    if path.is_dir() {
-                        // CSV file might be in this folder
-                        for entry in std::fs::read_dir(path)? {
-                            let entry = entry?;
-                            let path = entry.path();
-                            if path.extension().map(|os_str| os_str.to_str().unwrap_or("")) == Some("csv") {
-                                println!("Located csv file!");
-                                csv_path = Some(path);
-                                break 'search;
-                            }
-                        }
-                    } else {
-                        // CSV file is directly in ZIP file
-                        if path.extension().map(|os_str| os_str.to_str().unwrap_or("WAH")) == Some("csv") {
-                            println!("Located csv file!");
-                            csv_path = Some(path);
-                            break 'search;
-                        }
-                    }
-                }
-                let csv_path = csv_path.ok_or(std::fmt::Error)?;
-
-                // Compare CSV files
-                let mut changes = HashMap::new();
-                if let Ok(car_data_old) = car_checker::from_utf16_file(csv_path_old.to_str().unwrap_or("AUGH")) {
-                    if let Ok(car_data) = car_checker::from_utf16_file(csv_path.to_str().unwrap_or("AUGH")) {
-                        for (key, old_value) in car_data_old.iter() {
-                            let new_value = &car_data[key];
-                            if old_value != new_value {
-                                changes.insert(key.clone(), (old_value.clone(), new_value.clone()));
-                            }
-                        }
-                    }
-                }
+/// `!grant_role <capability> @role [series]`, for every series if none is given.
+#[command]
+async fn grant_role(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    change_role(ctx, msg, args, true).await
+}
 
-                std::fs::write(format!("registered/group_c/changes_{}.txt", car_name), format!("{:#?}", changes))?;
-            }
-        }
-        msg.reply(ctx, &format!("Skipped cars (aka no new/working version submitted):\n{}", skipped_cars.join("\n"))).await?;
-    }
+/// `!revoke_role <capability> @role [series]`
+#[command]
+async fn revoke_role(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    change_role(ctx, msg, args, false).await
+}
 
+async fn change_role(ctx: &Context, msg: &Message, mut args: Args, grant: bool) -> CommandResult {
+    let usage = format!("Usage: `{} <capability> @role [series]`, with one of {}", config::get().command(if grant { "grant_role" } else { "revoke_role" }), Capability::ALL.map(|capability| format!("`{}`", capability)).join(", "));
+    let capability = args.single::<String>().map_err(|_| usage.clone()).and_then(|capability| capability.parse::<Capability>());
+    let role = args.single::<RoleId>().map_err(|_| usage.clone());
+    let series_id = args.rest().trim().to_lowercase();
+    let (capability, role) = match (capability, role) {
+        (Ok(capability), Ok(role)) => (capability, *role.as_u64()),
+        (Err(reply), _) | (_, Err(reply)) => {
+            msg.reply(ctx, reply).await?;
+            return Ok(());
+        },
+    };
+    let series_id = match series::find(&series_id) {
+        _ if series_id.is_empty() => None,
+        Some(series) => Some(series.id.as_str()),
+        None => {
+            msg.reply(ctx, format!("Which series? Pass one of {}, or nothing for every series.", series::ids())).await?;
+            return Ok(());
+        },
+    };
+    let guild_id = msg.guild_id.map(|guild_id| *guild_id.as_u64()).unwrap_or_default();
+    let (Ok(reply) | Err(reply)) = Store::get(ctx).await.update(|data| update_role(data, guild_id, capability, role, series_id, grant)).await?;
+    msg.channel_id.send_message(ctx, |m| m.content(reply).allowed_mentions(|mentions| mentions.empty_roles())).await?;
     Ok(())
 }
 
-#[command]
-async fn dump_changes_gt1(ctx: &Context, msg: &Message) -> CommandResult {
-    if std::path::Path::new("registered/gt1_prev").exists() == false {
-        msg.reply(ctx, "There have not been previous gt1 submissions!").await?;
-    } else {
-        let mut skipped_cars = Vec::new();
-        for entry in std::fs::read_dir("registered/gt1_prev")? {
-            let entry = entry?;
-            let path = entry.path();
-            if !path.is_dir() && path.extension().map(|os_str| os_str.to_str().unwrap_or("AUGH")) == Some("zip") {
-                // Found a zip file
-                // Extracting the old car
-                let mut file = std::fs::File::open(path.clone())?;
-                let mut zip_bytes = Vec::new();
-                file.read_to_end(&mut zip_bytes)?;
-                let mut zip_reader = zip::ZipArchive::new(std::io::Cursor::new(zip_bytes.clone()))?;
-                zip_reader.extract("csv/tmp_old")?;
-
-                let car_name = path.file_name().map(|s| s.to_str().unwrap_or("AUGH")).unwrap_or("AUGH");
-
-                // Check if new car exists
-                if std::path::Path::new(&format!("registered/gt1/{}", car_name)).exists() == false {
-                    skipped_cars.push(car_name.to_string());
-                    continue;
-                }
+/// Grants a capability to a role of a guild, or revokes it, in a series or in every series if
+/// `series_id` is `None`.
+fn update_role(data: &mut Data, guild_id: u64, capability: Capability, role: u64, series_id: Option<&str>, grant: bool) -> Result<String, String> {
+    let roles = data.roles.entry(guild_id).or_default();
+    let changed = if grant { roles.grant(capability, role, series_id) } else { roles.revoke(capability, role, series_id) };
+    let scope = series_id.map(|series_id| format!("in `{}`", series_id)).unwrap_or_else(|| String::from("in every series"));
+    match (changed, grant) {
+        (true, true) => Ok(format!("<@&{}> is now a `{}` {}.", role, capability, scope)),
+        (true, false) => Ok(format!("<@&{}> is no longer a `{}` {}.", role, capability, scope)),
+        (false, true) => Err(format!("<@&{}> already is a `{}` {}!", role, capability, scope)),
+        (false, false) => Err(format!("<@&{}> isn't a `{}` {}!", role, capability, scope)),
+    }
+}
 
-                // Extracting the new car
-                let mut file = std::fs::File::open(&format!("registered/gt1/{}", car_name))?;
-                let mut zip_bytes = Vec::new();
-                file.read_to_end(&mut zip_bytes)?;
-                let mut zip_reader = zip::ZipArchive::new(std::io::Cursor::new(zip_bytes.clone()))?;
-                zip_reader.extract("csv/tmp")?;
-
-                // Find CSV files
-                let mut csv_path_old = None;
-                'search_old: for entry in std::fs::read_dir("csv/tmp_old")? {
-                    let entry = entry?;
-                    let path = entry.path();
-                    if path.is_dir() {
-                        // CSV file might be in this folder
-                        for entry in std::fs::read_dir(path)? {
-                            let entry = entry?;
-                            let path = entry.path();
-                            if path.extension().map(|os_str| os_str.to_str().unwrap_or("")) == Some("csv") {
-                                println!("Located csv file!");
-                                csv_path_old = Some(path);
-                                break 'search_old;
-                            }
-                        }
-                    } else {
-                        // CSV file is directly in ZIP file
-                        if path.extension().map(|os_str| os_str.to_str().unwrap_or("WAH")) == Some("csv") {
-                            println!("Located csv file!");
-                            csv_path_old = Some(path);
-                            break 'search_old;
-                        }
-                    }
-                }
-                let csv_path_old = csv_path_old.ok_or(std::fmt::Error)?;
-
-                let mut csv_path = None;
-                'search: for entry in std::fs::read_dir("csv/tmp")? {
-                    let entry = entry?;
-                    let path = entry.path();
-                    if path.is_dir() {
-                        // CSV file might be in this folder
-                        for entry in std::fs::read_dir(path)? {
-                            let entry = entry?;
-                            let path = entry.path();
-                            if path.extension().map(|os_str| os_str.to_str().unwrap_or("")) == Some("csv") {
-                                println!("Located csv file!");
-                                csv_path = Some(path);
-                                break 'search;
-                            }
-                        }
-                    } else {
-                        // CSV file is directly in ZIP file
-                        if path.extension().map(|os_str| os_str.to_str().unwrap_or("WAH")) == Some("csv") {
-                            println!("Located csv file!");
-                            csv_path = Some(path);
-                            break 'search;
-                        }
-                    }
-                }
-                let csv_path = csv_path.ok_or(std::fmt::Error)?;
-
-                // Compare CSV files
-                let mut changes = HashMap::new();
-                if let Ok(car_data_old) = car_checker::from_utf16_file(csv_path_old.to_str().unwrap_or("AUGH")) {
-                    if let Ok(car_data) = car_checker::from_utf16_file(csv_path.to_str().unwrap_or("AUGH")) {
-                        for (key, old_value) in car_data_old.iter() {
-                            let new_value = &car_data[key];
-                            if old_value != new_value {
-                                changes.insert(key.clone(), (old_value.clone(), new_value.clone()));
-                            }
-                        }
-                    }
-                }
+/// `!roles`
+#[command]
+async fn roles(ctx: &Context, msg: &Message) -> CommandResult {
+    let guild_id = msg.guild_id.map(|guild_id| *guild_id.as_u64()).unwrap_or_default();
+    let content = Store::get(ctx).await.read(|data| roles_of(data, guild_id)).await;
+    msg.channel_id.send_message(ctx, |m| m.content(content).allowed_mentions(|mentions| mentions.empty_roles())).await?;
+    Ok(())
+}
 
-                std::fs::write(format!("registered/gt1/changes_{}.txt", car_name), format!("{:#?}", changes))?;
-            }
-        }
-        msg.reply(ctx, &format!("Skipped cars (aka no new/working version submitted):\n{}", skipped_cars.join("\n"))).await?;
+/// Lists which roles of a guild have which capabilities.
+fn roles_of(data: &Data, guild_id: u64) -> String {
+    let list = |capabilities: &std::collections::BTreeMap<Capability, std::collections::BTreeSet<u64>>| {
+        capabilities.iter()
+            .filter(|(_, roles)| !roles.is_empty())
+            .map(|(capability, roles)| format!("`{}`: {}\n", capability, roles.iter().map(|role| format!("<@&{}>", role)).collect::<Vec<_>>().join(", ")))
+            .collect::<String>()
+    };
+    let roles = data.roles.get(&guild_id).cloned().unwrap_or_default();
+    let mut content = format!("**Every series**\n{}", list(&roles.global));
+    for (series_id, capabilities) in &roles.series {
+        content.push_str(&format!("**{}**\n{}", series_title(series_id), list(capabilities)));
     }
+    format!("{}\nWhile no role has a capability, anyone who can manage roles has it.", content)
+}
 
+/// `!season <series>`
+#[command]
+async fn season(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let series = match series::find(args.rest()) {
+        Some(series) => series,
+        None => {
+            msg.reply(ctx, format!("Which series? Pass one of {}", series::ids())).await?;
+            return Ok(());
+        },
+    };
+    let content = Store::get(ctx).await.read(|data| season_of(data, series)).await;
+    msg.reply(ctx, content).await?;
     Ok(())
 }
 
+/// Sums up every round of a series so far.
+fn season_of(data: &Data, series: &Series) -> String {
+    let settings = data.series_settings(&series.id);
+    let mut content = format!("**{}**\n", series.name);
+    for round in 1..settings.round {
+        match data.locked(&series.id, round) {
+            Some(snapshot) => content.push_str(&format!("Round {}: {} car(s), locked <t:{}:f>\n", round, snapshot.cars.len(), snapshot.locked_at)),
+            None => content.push_str(&format!("Round {}: {} car(s), finalized before rounds were locked\n", round, data.cars(&series.id, round).len())),
+        }
+    }
+    content.push_str(&format!(
+        "Round {}: {} car(s) so far, submissions {}{}\n",
+        settings.round,
+        data.cars(&series.id, settings.round).len(),
+        if settings.submissions_open { "open" } else { "closed" },
+        settings.deadline.map(|deadline| format!(", deadline <t:{}:f>", deadline)).unwrap_or_default(),
+    ));
+    content
+}
+
+/// `!round_changes <series> [from] [to]`: writes what every entry changed between two rounds,
+/// by default the previous and the current one, to `changes_<from>_<to>_<driver>_<entry>.md` and
+/// `.json` in the series' registration directory, with any changes to frozen components flagged
+/// at the top.
 #[command]
-async fn submit_group_c(ctx: &Context, msg: &Message) -> CommandResult {
-    if msg.attachments.len() != 1 {
-        msg.reply(ctx, "Attach 1 file! No more, no less. If your zip submission is too big, get in contact with <@183315569745985545> for now.").await?;
-        return Ok(());
+async fn round_changes(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let series = series::find(&args.single::<String>().unwrap_or_default());
+    let from = args.single::<u32>().ok();
+    let to = args.single::<u32>().ok();
+    let series = match series {
+        Some(series) => series,
+        None => {
+            msg.reply(ctx, format!("Usage: `{} <series> [from round] [to round]`, with one of {}", config::get().command("round_changes"), series::ids())).await?;
+            return Ok(());
+        },
+    };
+    match compare_rounds(ctx, series, from, to).await? {
+        (content, Some((name, report))) => {
+            msg.channel_id.send_message(ctx, |m| m.content(content).add_file((report.as_bytes(), name.as_str()))).await?;
+        },
+        (content, None) => {
+            msg.reply(ctx, content).await?;
+        },
     }
+    Ok(())
+}
 
-    if let Ok(zip_bytes) = msg.attachments[0].download().await {
-        let mut zip_name = format!("csv/{}", msg.attachments[0].filename.clone());
-        zip_name.pop();
-        zip_name.pop();
-        zip_name.pop();
-        zip_name.pop();
-        let mut zip_reader = zip::ZipArchive::new(std::io::Cursor::new(zip_bytes.clone()))?;
-        zip_reader.extract(&zip_name)?;
-        msg.reply(ctx, "Zip file extracted!").await?;
-
-        // Find CSV file
-        let mut csv_path = None;
-        'search: for entry in std::fs::read_dir(&zip_name)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_dir() {
-                // CSV file might be in this folder
-                for entry in std::fs::read_dir(path)? {
-                    let entry = entry?;
-                    let path = entry.path();
-                    if path.extension().map(|os_str| os_str.to_str().unwrap_or("")) == Some("csv") {
-                        println!("Located csv file!");
-                        csv_path = Some(path);
-                        break 'search;
-                    }
-                }
-            } else {
-                // CSV file is directly in ZIP file
-                if path.extension().map(|os_str| os_str.to_str().unwrap_or("WAH")) == Some("csv") {
-                    println!("Located csv file!");
-                    csv_path = Some(path);
-                    break 'search;
-                }
-            }
+/// Writes what every entry changed between two rounds, by default the previous and the current
+/// one. Returns a summary to reply with and, if the rounds could be compared, the name and text
+/// of a report with every entry, which is too long for a message.
+async fn compare_rounds(ctx: &Context, series: &Series, from: Option<u32>, to: Option<u32>) -> Result<(String, Option<(String, String)>), CommandError> {
+    let store = Store::get(ctx).await;
+    let current_round = store.read(|data| data.series_settings(&series.id).round).await;
+    let from = from.unwrap_or(current_round - 1);
+    let to = to.unwrap_or(current_round);
+    if from == 0 || from >= to || to > current_round {
+        return Ok((format!("Pick two rounds from 1 to {}, the earlier one first!", current_round), None));
+    }
+    let cars = store.read(|data| {
+        let mut entries: BTreeMap<(u64, u32), (Option<Submission>, Option<Submission>)> = BTreeMap::new();
+        for car in data.cars(&series.id, from) {
+            entries.entry((car.driver, car.entry)).or_default().0 = data.submission(car.submission).cloned();
         }
-        if csv_path.is_none() {
-            msg.reply(ctx, "Failed to find CSV file in your zip! Did you send the right zip file?").await?;
+        for car in data.cars(&series.id, to) {
+            entries.entry((car.driver, car.entry)).or_default().1 = data.submission(car.submission).cloned();
         }
+        entries
+    }).await;
 
-        let csv_path = csv_path.unwrap();
-        if let Ok(car_data) = car_checker::from_utf16_file(csv_path.to_str().unwrap_or("WAH")) {
-            let result = car_checker::regulations::mcs_s1_group_c::MCS_S1_Group_C::default().check(car_data);
-            match result {
-                Ok(_) => {
-                    let pathbuf = std::path::Path::new(&format!("registered/group_c/{}", msg.attachments[0].filename)).to_owned();
-                    if let Err(_) = std::fs::write(pathbuf, zip_bytes) {
-                        msg.reply(ctx, "Seems like your car is good to go! Something went wrong while registering however. Please ping any of the EMs for this series").await?;
-                    } else {
-                        msg.reply(ctx, "Seems like your car is good to go! Registered it for the next event, feel free to send in new versions whenever you want! **Note:** Please keep in mind that part changes are not checked by me. It'll be done manually by the EMs.").await?;
-                    }
-                },
-                Err(e) => { msg.reply(ctx, format!("Your car seems to break the regulations. For now, I can only show you the first issue encountered, which is `{}`\nThis version has not been saved for the event.", e)).await?; }
-            };
+    let regulations = match RuleSet::from_file(&series.regulations) {
+        Ok(regulations) => regulations,
+        Err(e) => {
+            println!("Failed to load regulations: {:?}", e);
+            return Ok((String::from("Couldn't load the regulations for this series!"), None));
+        },
+    };
+
+    let workspace = Workspace::new_in(&series.registration_dir)?;
+    let mut changed_cars = Vec::new();
+    let mut unchanged_cars = Vec::new();
+    let mut skipped_cars = Vec::new();
+    let mut unreadable_cars = Vec::new();
+    let mut flagged_cars = Vec::new();
+    for ((driver, entry), cars) in cars {
+        let (old, new) = match cars {
+            (Some(old), Some(new)) if old.id == new.id || (!old.hash.is_empty() && old.hash == new.hash) => {
+                unchanged_cars.push(format!("<@{}> entry {}", driver, entry));
+                continue;
+            },
+            (Some(old), Some(new)) => (old, new),
+            (None, _) => {
+                skipped_cars.push(format!("<@{}> entry {}: new in round {}", driver, entry, to));
+                continue;
+            },
+            (_, None) => {
+                skipped_cars.push(format!("<@{}> entry {}: not entered in round {}", driver, entry, to));
+                continue;
+            },
+        };
+
+        let (diff, review) = match (submission::read_car(&old), submission::read_car(&new)) {
+            (Ok(old), Ok(new)) => {
+                let diff = CarDiff::between(&old, &new);
+                let review = regulations.review(&diff, &new);
+                (diff, review)
+            },
+            (Err(e), _) | (_, Err(e)) => {
+                unreadable_cars.push(format!("<@{}> entry {}: {}", driver, entry, e));
+                continue;
+            },
+        };
+        let name = format!("{}/changes_{}_{}_{}_{}", series.registration_dir, from, to, driver, entry);
+        let markdown = format!("{}\n{}", review.to_markdown(), diff.to_markdown());
+        let json = serde_json::json!({ "flags": review.flags, "fields": diff.fields });
+        workspace.write_atomically(format!("{}.md", name), markdown.as_bytes())?;
+        workspace.write_atomically(format!("{}.json", name), serde_json::to_string_pretty(&json)?.as_bytes())?;
+        if review.is_clean() {
+            changed_cars.push(format!("<@{}> entry {}: {} change(s)", driver, entry, diff.fields.len()));
         } else {
-            msg.reply(ctx, "Something went wrong with loading the file!").await?;
+            changed_cars.push(format!("<@{}> entry {}: {} change(s), **{} flagged**", driver, entry, diff.fields.len(), review.flags.len()));
+            flagged_cars.push(format!("<@{}> entry {}:\n{}", driver, entry, review));
         }
+    }
+    let content = format!(
+        "Changes from round {} to round {}: {} car(s) changed, {} of them to frozen components. {} unchanged, {} only in one of the rounds, {} couldn't be read.",
+        from, to, changed_cars.len(), flagged_cars.len(), unchanged_cars.len(), skipped_cars.len(), unreadable_cars.len(),
+    );
+    let mut report = format!("# {} changes from round {} to round {}\n\n## Changed\n{}\n", series.name, from, to, changed_cars.join("\n"));
+    if !flagged_cars.is_empty() {
+        report.push_str(&format!("\n## Changes to frozen components\n```\n{}```\n", flagged_cars.join("")));
+    }
+    if !unchanged_cars.is_empty() {
+        report.push_str(&format!("\n## Unchanged\n{}\n", unchanged_cars.join("\n")));
+    }
+    if !skipped_cars.is_empty() {
+        report.push_str(&format!("\n## Only in one of the rounds\n{}\n", skipped_cars.join("\n")));
+    }
+    if !unreadable_cars.is_empty() {
+        report.push_str(&format!("\n## Couldn't read\n{}\n", unreadable_cars.join("\n")));
+    }
+    Ok((content, Some((format!("changes_{}_{}_{}.md", series.id, from, to), report))))
+}
 
-        std::fs::remove_dir_all(&zip_name)?;
-    } else {
-        msg.reply(ctx, "Error encountered while downloading file!").await?;
+/// `!history <series> @driver`
+#[command]
+async fn history(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let series = series::find(&args.single::<String>().unwrap_or_default());
+    let driver = args.single::<UserId>();
+    let (series, driver) = match (series, driver) {
+        (Some(series), Ok(driver)) => (series, *driver.as_u64()),
+        _ => {
+            msg.reply(ctx, format!("Usage: `{} <series> @driver`, with one of {}", config::get().command("history"), series::ids())).await?;
+            return Ok(());
+        },
+    };
+    let content = Store::get(ctx).await.read(|data| history_of(data, series, driver)).await;
+    msg.channel_id.send_message(ctx, |m| m.content(content).allowed_mentions(|mentions| mentions.empty_users())).await?;
+    Ok(())
+}
+
+/// Lists every version a driver submitted for a series. If they don't all fit in a message, the
+/// oldest are left out.
+fn history_of(data: &Data, series: &Series, driver: u64) -> String {
+    let mut lines = Vec::new();
+    for submission in data.history(&series.id, driver) {
+        let current = data.current(&series.id, submission.round, driver, submission.entry).map(|current| current.id) == Some(submission.id);
+        let verdict = match &submission.verdict {
+            Verdict::Passed => String::from("passed"),
+            Verdict::Rejected { issues } => format!("rejected, {} issue(s)", issues.len()),
+        };
+        lines.push(format!("`#{}` round {} entry {} - <t:{}:f> - `{}` ({}) {}{}{}\n",
+            submission.id,
+            submission.round,
+            submission.entry,
+            submission.submitted_at,
+            submission.filename,
+            &submission.hash[..submission.hash.len().min(12)],
+            verdict,
+            submission.restored_from.map(|id| format!(", rolled back to #{}", id)).unwrap_or_default(),
+            if current { " **(current)**" } else { "" },
+        ));
+    }
+    if lines.is_empty() {
+        return format!("<@{}> hasn't submitted anything for {} yet!", driver, series.name);
+    }
+    let mut shown = 0;
+    let mut length = 0;
+    for line in lines.iter().rev() {
+        if length + line.len() > 1700 {
+            break;
+        }
+        length += line.len();
+        shown += 1;
     }
+    let mut content = String::new();
+    if shown < lines.len() {
+        content.push_str(&format!("...and {} older ones\n", lines.len() - shown));
+    }
+    content.extend(lines.drain(lines.len() - shown..));
+    format!("{} submissions of <@{}>:\n{}", series.name, driver, content)
+}
 
+/// `!diff <old version> <new version>`
+#[command]
+async fn diff(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let mut version = || args.single::<String>().unwrap_or_default().trim_start_matches('#').parse::<u64>().unwrap_or_default();
+    let (old, new) = (version(), version());
+    match compare_versions(ctx, old, new).await {
+        Ok(embed) => msg.channel_id.send_message(ctx, |m| m.set_embed(embed)).await?,
+        Err(reply) => msg.reply(ctx, reply).await?,
+    };
     Ok(())
 }
 
+/// Lists what changed between two versions, or what to reply with instead.
+async fn compare_versions(ctx: &Context, old: u64, new: u64) -> Result<CreateEmbed, String> {
+    let versions = Store::get(ctx).await.read(|data| [old, new].map(|id| data.submission(id).cloned())).await;
+    let (old, new) = match versions {
+        [Some(old), Some(new)] => (old, new),
+        _ => return Err(format!("Usage: `{} <old version> <new version>`, see `{}` for the versions.", config::get().command("diff"), config::get().command("history"))),
+    };
+    let (diff, new_car) = match (submission::read_car(&old), submission::read_car(&new)) {
+        (Ok(old), Ok(new)) => (CarDiff::between(&old, &new), new),
+        (Err(e), _) | (_, Err(e)) => return Err(format!("Couldn't read the cars: {}", e)),
+    };
+    // Frozen components only mean something between versions of the same series
+    let review = series::find(&new.series)
+        .filter(|_| old.series == new.series)
+        .and_then(|series| RuleSet::from_file(&series.regulations).ok())
+        .map(|regulations| regulations.review(&diff, &new_car))
+        .unwrap_or_default();
+    let mut embed = CreateEmbed::default();
+    embed.title(format!("#{} → #{}", old.id, new.id));
+    embed.description(if diff.is_empty() { String::from("No changes.") } else { format!("{} → {}", old.car, new.car) });
+    if !review.is_clean() {
+        let flags: String = review.flags.iter().map(|flag| format!("**{}**: {}\n", flag.field, flag.message)).collect();
+        embed.field(format!("Frozen components ({})", review.flags.len()), flags.chars().take(1024).collect::<String>(), false);
+    }
+    embed.fields(diff.to_embed_fields());
+    Ok(embed)
+}
+
+/// `!fetch_submission <version>`
 #[command]
-async fn submit_gt1(ctx: &Context, msg: &Message) -> CommandResult {
-    if msg.attachments.len() != 1 {
-        msg.reply(ctx, "Attach 1 file! No more, no less. If your zip submission is too big, get in contact with <@183315569745985545> for now.").await?;
-        return Ok(());
+async fn fetch_submission(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let id = args.single::<String>().unwrap_or_default().trim_start_matches('#').parse::<u64>().unwrap_or_default();
+    let (submission, file) = match Store::get(ctx).await.read(|data| kept_file(data, id)).await {
+        Ok(kept) => kept,
+        Err(reply) => {
+            msg.reply(ctx, reply).await?;
+            return Ok(());
+        },
+    };
+    let zip_bytes = std::fs::read(&file)?;
+    msg.channel_id.send_files(ctx, vec![(zip_bytes.as_slice(), submission.filename.as_str())], |m| m.content(format!("Version #{} of <@{}>", submission.id, submission.driver)).allowed_mentions(|mentions| mentions.empty_users())).await?;
+    Ok(())
+}
+
+/// A submitted version and where its zip is kept, or what to reply with instead.
+fn kept_file(data: &Data, id: u64) -> Result<(Submission, String), String> {
+    let submission = data.submission(id).cloned()
+        .ok_or_else(|| format!("Which version? Use `{} <version>`, see `{}` for the versions.", config::get().command("fetch_submission"), config::get().command("history")))?;
+    match submission.file.clone() {
+        Some(file) => Ok((submission, file)),
+        None => Err(format!("Version #{} didn't pass the checks, so it wasn't kept.", id)),
     }
+}
 
-    if let Ok(zip_bytes) = msg.attachments[0].download().await {
-        let mut zip_name = format!("csv/{}", msg.attachments[0].filename.clone());
-        zip_name.pop();
-        zip_name.pop();
-        zip_name.pop();
-        zip_name.pop();
-        let mut zip_reader = zip::ZipArchive::new(std::io::Cursor::new(zip_bytes.clone()))?;
-        zip_reader.extract(&zip_name)?;
-        msg.reply(ctx, "Zip file extracted!").await?;
-
-        // Find CSV file
-        let mut csv_path = None;
-        'search: for entry in std::fs::read_dir(&zip_name)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_dir() {
-                // CSV file might be in this folder
-                for entry in std::fs::read_dir(path)? {
-                    let entry = entry?;
-                    let path = entry.path();
-                    if path.extension().map(|os_str| os_str.to_str().unwrap_or("")) == Some("csv") {
-                        println!("Located csv file!");
-                        csv_path = Some(path);
-                        break 'search;
-                    }
-                }
-            } else {
-                // CSV file is directly in ZIP file
-                if path.extension().map(|os_str| os_str.to_str().unwrap_or("WAH")) == Some("csv") {
-                    println!("Located csv file!");
-                    csv_path = Some(path);
-                    break 'search;
-                }
-            }
-        }
-        if csv_path.is_none() {
-            msg.reply(ctx, "Failed to find CSV file in your zip! Did you send the right zip file?").await?;
-        }
+/// `!rollback <version>`
+#[command]
+async fn rollback(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let id = args.single::<String>().unwrap_or_default().trim_start_matches('#').parse::<u64>().unwrap_or_default();
+    let (Ok(reply) | Err(reply)) = Store::get(ctx).await.update(|data| roll_back_to(data, id, submission::now())).await?;
+    msg.reply(ctx, reply).await?;
+    Ok(())
+}
 
-        let csv_path = csv_path.unwrap();
-        if let Ok(car_data) = car_checker::from_utf16_file(csv_path.to_str().unwrap_or("WAH")) {
-            let result = car_checker::regulations::mcs_s1_gt1::MCS_S1_GT1::default().check(car_data);
-            match result {
-                Ok(_) => {
-                    if let Err(_) = std::fs::write(format!("registered/gt1/{}", msg.attachments[0].filename.clone()), zip_bytes) {
-                        msg.reply(ctx, "Seems like your car is good to go! Something went wrong while registering however. Please ping any of the EMs for this series").await?;
-                    } else {
-                        msg.reply(ctx, "Seems like your car is good to go! Registered it for the next event, feel free to send in new versions whenever you want!").await?;
-                    }
+fn roll_back_to(data: &mut Data, id: u64, now: u64) -> Result<String, String> {
+    let restored = data.roll_back(id, now)?;
+    Ok(format!("Rolled back to version #{}, it's registered again as #{}.", id, restored))
+}
+
+/// `!submit <series> [entry] [csv=<number or name>]`
+#[command]
+async fn submit(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let series = match series::find(&args.single::<String>().unwrap_or_default()) {
+        Some(series) => series,
+        None => {
+            msg.reply(ctx, format!("Which series are you submitting for? Use `{} <series>` with one of: {}", config::get().command("submit"), series::ids())).await?;
+            return Ok(());
+        },
+    };
+    let mut entry = 1;
+    let mut csv = None;
+    for arg in args.iter::<String>().quoted().filter_map(Result::ok) {
+        match arg.strip_prefix("csv=") {
+            Some(choice) => csv = Some(choice.to_string()),
+            None => match arg.parse::<u32>().ok().filter(|entry| *entry > 0) {
+                Some(choice) => entry = choice,
+                None => {
+                    msg.reply(ctx, format!("`{}` isn't an entry. Usage: `{} <series> [entry] [csv=<number or name>]`, with the entry counting from 1.", arg, config::get().command("submit"))).await?;
+                    return Ok(());
                 },
-                Err(e) => { msg.reply(ctx, format!("Your car seems to break the regulations. For now, I can only show you the first issue encountered, which is `{}`\nThis version has not been saved for the event.", e)).await?; }
-            };
-        } else {
-            msg.reply(ctx, "Something went wrong with loading the file!").await?;
+            },
         }
+    }
+    let reply = submission::submit(ctx, *msg.author.id.as_u64(), &msg.attachments, series, entry, csv.as_deref()).await?;
+    msg.reply(ctx, reply).await?;
+    Ok(())
+}
 
-        std::fs::remove_dir_all(&zip_name)?;
-    } else {
-        msg.reply(ctx, "Error encountered while downloading file!").await?;
+#[command]
+async fn submit_group_c(ctx: &Context, msg: &Message) -> CommandResult {
+    let reply = match series::configured("group_c") {
+        Ok(series) => submission::submit(ctx, *msg.author.id.as_u64(), &msg.attachments, series, 1, None).await?,
+        Err(reply) => reply,
+    };
+    msg.reply(ctx, reply).await?;
+    Ok(())
+}
+
+#[command]
+async fn submit_gt1(ctx: &Context, msg: &Message) -> CommandResult {
+    let reply = match series::configured("gt1") {
+        Ok(series) => submission::submit(ctx, *msg.author.id.as_u64(), &msg.attachments, series, 1, None).await?,
+        Err(reply) => reply,
+    };
+    msg.reply(ctx, reply).await?;
+    Ok(())
+}
+
+/// `!my_car [series]`
+#[command]
+async fn my_car(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let reply = Store::get(ctx).await.read(|data| my_cars(data, *msg.author.id.as_u64(), args.rest().trim())).await;
+    msg.reply(ctx, reply).await?;
+    Ok(())
+}
+
+/// Lists the cars a driver has registered for the next round of every series, or just the one
+/// given.
+fn my_cars(data: &Data, driver: u64, series_id: &str) -> String {
+    let mut content = String::new();
+    for series in series::all().iter().filter(|series| series_id.is_empty() || series.id.eq_ignore_ascii_case(series_id)) {
+        let round = data.series_settings(&series.id).round;
+        for submission in data.registered(&series.id, round, driver) {
+            let car = if submission.car.is_empty() { &submission.filename } else { &submission.car };
+            content.push_str(&format!("{} entry {}: {} - version `#{}`, submitted <t:{}:R>\n", series.name, submission.entry, car, submission.id, submission.submitted_at));
+        }
+    }
+    if content.is_empty() {
+        content = String::from("You don't have any cars registered for the next round!");
     }
+    content
+}
 
+/// `!set_entry_limit <series> <entries>`
+#[command]
+async fn set_entry_limit(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let series = series::find(&args.single::<String>().unwrap_or_default());
+    let (series, max_entries) = match (series, args.single::<u32>()) {
+        (Some(series), Ok(max_entries)) if max_entries > 0 => (series, max_entries),
+        _ => {
+            msg.reply(ctx, format!("Usage: `{} <series> <entries>`, with one of {}", config::get().command("set_entry_limit"), series::ids())).await?;
+            return Ok(());
+        },
+    };
+    let reply = Store::get(ctx).await.update(|data| Ok(set_series_entry_limit(data, series, max_entries))).await??;
+    msg.reply(ctx, reply).await?;
     Ok(())
 }
+
+fn set_series_entry_limit(data: &mut Data, series: &Series, max_entries: u32) -> String {
+    data.series.entry(series.id.clone()).or_default().max_entries = max_entries;
+    format!("Drivers can now enter up to {} car(s) in {}.", max_entries, series.name)
+}
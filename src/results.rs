@@ -0,0 +1,165 @@
+use serde::{Serialize, Deserialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Finished,
+    /// Did not finish. Classified behind every finisher, without points.
+    Dnf,
+    /// Disqualified. Not classified at all.
+    Dsq,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Finish {
+    pub driver: u64,
+    pub status: Status,
+}
+
+/// The full results of a single race.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Event {
+    pub id: String,
+    /// `YYYY-MM-DD`
+    pub date: String,
+    pub track: String,
+    pub series: String,
+    /// Finishers in order, then DNFs, then DSQs.
+    pub finishes: Vec<Finish>,
+    pub pole: Option<u64>,
+    pub fastest_lap: Option<u64>,
+}
+
+/// Checks a date is written as `YYYY-MM-DD`.
+pub fn valid_date(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('-').collect();
+    match parts.as_slice() {
+        [year, month, day] => {
+            let number = |part: &str, len: usize, max: u32| part.len() == len && part.parse::<u32>().is_ok_and(|n| (1..=max).contains(&n));
+            number(year, 4, 9999) && number(month, 2, 12) && number(day, 2, 31)
+        },
+        _ => false,
+    }
+}
+
+impl Event {
+    pub fn new(id: &str, date: &str, track: &str, series: &str) -> Self {
+        Self {
+            id: String::from(id),
+            date: String::from(date),
+            track: String::from(track),
+            series: String::from(series),
+            finishes: Vec::new(),
+            pole: None,
+            fastest_lap: None,
+        }
+    }
+
+    /// The driver's finishing position, starting at 1. DNFs and DSQs don't have one.
+    pub fn position(&self, driver: u64) -> Option<usize> {
+        self.finishes.iter().filter(|finish| finish.status == Status::Finished).position(|finish| finish.driver == driver).map(|i| i + 1)
+    }
+
+    /// How the driver's race ended, if they're in the results.
+    pub fn status(&self, driver: u64) -> Option<Status> {
+        self.finishes.iter().find(|finish| finish.driver == driver).map(|finish| finish.status)
+    }
+
+    /// Everyone who took part or scored a bonus.
+    pub fn drivers(&self) -> impl Iterator<Item = u64> + '_ {
+        self.finishes.iter().map(|finish| finish.driver).chain(self.pole).chain(self.fastest_lap)
+    }
+
+    /// Replaces the whole classification.
+    pub fn set_results(&mut self, finished: &[u64], dnf: &[u64], dsq: &[u64]) {
+        self.finishes.clear();
+        for (drivers, status) in [(finished, Status::Finished), (dnf, Status::Dnf), (dsq, Status::Dsq)] {
+            for driver in drivers {
+                self.place(*driver, status, None);
+            }
+        }
+    }
+
+    /// Takes a driver out of the results. Returns false if they weren't in them.
+    pub fn remove(&mut self, driver: u64) -> bool {
+        let len = self.finishes.len();
+        self.finishes.retain(|finish| finish.driver != driver);
+        self.finishes.len() != len
+    }
+
+    /// Moves a driver to a finishing position (starting at 1), or to the DNF or DSQ group.
+    /// A position past the last finisher puts them last among the finishers.
+    pub fn place(&mut self, driver: u64, status: Status, position: Option<usize>) {
+        self.remove(driver);
+        let finished = self.finishes.iter().filter(|finish| finish.status == Status::Finished).count();
+        let dnf = self.finishes.iter().filter(|finish| finish.status == Status::Dnf).count();
+        let index = match status {
+            Status::Finished => position.map_or(finished, |position| position.saturating_sub(1).min(finished)),
+            Status::Dnf => finished + dnf,
+            Status::Dsq => self.finishes.len(),
+        };
+        self.finishes.insert(index, Finish { driver, status });
+    }
+
+    /// Moves finishers to just behind the top `positions`, keeping their order among themselves,
+    /// so everyone who finished behind them moves up.
+    pub fn demote(&mut self, drivers: &[u64], positions: usize) {
+        let (demoted, kept): (Vec<Finish>, Vec<Finish>) = self.finishes.drain(..).partition(|finish| finish.status == Status::Finished && drivers.contains(&finish.driver));
+        self.finishes = kept;
+        let finished = self.finishes.iter().filter(|finish| finish.status == Status::Finished).count();
+        let index = positions.min(finished);
+        self.finishes.splice(index..index, demoted);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_skip_dnf_and_dsq() {
+        let mut event = Event::new("r1", "2022-11-05", "Nordschleife", "gt1");
+        event.set_results(&[1, 2, 3], &[4], &[5]);
+        assert_eq!(event.position(3), Some(3));
+        assert_eq!(event.position(4), None);
+        assert_eq!(event.finishes[4], Finish { driver: 5, status: Status::Dsq });
+
+        // A DSQ for the winner promotes everyone else
+        event.place(1, Status::Dsq, None);
+        assert_eq!((event.position(2), event.position(3)), (Some(1), Some(2)));
+        assert_eq!(event.finishes.iter().map(|finish| finish.driver).collect::<Vec<_>>(), [2, 3, 4, 5, 1]);
+    }
+
+    #[test]
+    fn edits() {
+        let mut event = Event::new("r1", "2022-11-05", "Nordschleife", "gt1");
+        event.set_results(&[1, 2, 3], &[4], &[]);
+        event.place(3, Status::Finished, Some(1));
+        assert_eq!(event.position(3), Some(1));
+        event.place(4, Status::Finished, Some(10));
+        assert_eq!(event.position(4), Some(4));
+        assert!(event.remove(2));
+        assert!(!event.remove(2));
+        assert_eq!(event.finishes.iter().map(|finish| finish.driver).collect::<Vec<_>>(), [3, 1, 4]);
+    }
+
+    #[test]
+    fn demotions() {
+        let mut event = Event::new("r1", "2022-11-05", "Nordschleife", "gt1");
+        event.set_results(&[1, 2, 3, 4, 5], &[6], &[]);
+        event.demote(&[1], 1);
+        assert_eq!((event.position(2), event.position(1)), (Some(1), Some(2)));
+        // Both leave the podium, rather than the second one pushing the first back onto it
+        event.demote(&[2, 1], 3);
+        assert_eq!(event.finishes.iter().map(|finish| finish.driver).collect::<Vec<_>>(), [3, 4, 5, 2, 1, 6]);
+        assert_eq!(event.status(1), Some(Status::Finished));
+    }
+
+    #[test]
+    fn dates() {
+        assert!(valid_date("2022-11-05"));
+        assert!(!valid_date("2022-13-05"));
+        assert!(!valid_date("05-11-2022"));
+        assert!(!valid_date("2022-11-5"));
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+
+use crate::results::{Event, Status};
+
+/// How a series hands out championship points.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PointsSystem {
+    /// Points for finishing 1st, 2nd, 3rd, ... Anyone further back scores nothing.
+    pub positions: Vec<u32>,
+    pub pole: u32,
+    pub fastest_lap: u32,
+    /// How many of each driver's worst rounds don't count.
+    pub dropped_rounds: usize,
+}
+
+impl Default for PointsSystem {
+    fn default() -> Self {
+        Self::f1()
+    }
+}
+
+impl PointsSystem {
+    pub fn f1() -> Self {
+        Self { positions: vec![25, 18, 15, 12, 10, 8, 6, 4, 2, 1], pole: 0, fastest_lap: 1, dropped_rounds: 0 }
+    }
+
+    /// Points scored by a driver in a single event. DNFs only keep their pole or fastest lap bonus,
+    /// DSQs don't score at all.
+    pub fn round_points(&self, event: &Event, driver: u64) -> u32 {
+        if event.status(driver) == Some(Status::Dsq) {
+            return 0;
+        }
+        let mut points = event.position(driver).and_then(|position| self.positions.get(position - 1)).copied().unwrap_or(0);
+        if event.pole == Some(driver) {
+            points += self.pole;
+        }
+        if event.fastest_lap == Some(driver) {
+            points += self.fastest_lap;
+        }
+        points
+    }
+
+    /// Reads a setting like `positions=25,18,15`, `pole=1`, `fastest_lap=1` or `drop=2`,
+    /// or `f1` to go back to the default.
+    pub fn apply(&mut self, setting: &str) -> Result<(), String> {
+        if setting.eq_ignore_ascii_case("f1") {
+            *self = Self::f1();
+            return Ok(());
+        }
+        let (key, value) = setting.split_once('=').ok_or_else(|| format!("Expected `key=value`, got `{}`", setting))?;
+        let number = |value: &str| value.trim().parse::<u32>().map_err(|_| format!("`{}` is not a number", value));
+        match key.trim() {
+            "positions" => self.positions = value.split(',').map(number).collect::<Result<_, _>>()?,
+            "pole" => self.pole = number(value)?,
+            "fastest_lap" | "fl" => self.fastest_lap = number(value)?,
+            "drop" | "dropped_rounds" => self.dropped_rounds = number(value)? as usize,
+            key => return Err(format!("Unknown setting `{}`", key)),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Standing {
+    pub driver: u64,
+    pub points: u32,
+    pub wins: u32,
+    pub podiums: u32,
+}
+
+/// The championship order: most points first, then most wins, then most podiums.
+pub fn standings(system: &PointsSystem, events: &[Event]) -> Vec<Standing> {
+    let mut drivers: Vec<u64> = events.iter().flat_map(Event::drivers).collect();
+    drivers.sort_unstable();
+    drivers.dedup();
+
+    let mut standings: Vec<Standing> = drivers
+        .into_iter()
+        .map(|driver| {
+            // Events a driver missed count as zero, so they're the first to be dropped
+            let mut points: Vec<u32> = events.iter().map(|event| system.round_points(event, driver)).collect();
+            points.sort_unstable();
+            let counted = points.iter().skip(system.dropped_rounds.min(points.len().saturating_sub(1))).sum();
+            let positions: Vec<usize> = events.iter().filter_map(|event| event.position(driver)).collect();
+            Standing {
+                driver,
+                points: counted,
+                wins: positions.iter().filter(|position| **position == 1).count() as u32,
+                podiums: positions.iter().filter(|position| **position <= 3).count() as u32,
+            }
+        })
+        .collect();
+    standings.sort_by(|a, b| (b.points, b.wins, b.podiums, a.driver).cmp(&(a.points, a.wins, a.podiums, b.driver)));
+    standings
+}
+
+/// Adds wins and podiums that were counted by hand, before results were recorded.
+pub fn with_manual_counts(mut standings: Vec<Standing>, counts: &HashMap<u64, (u32, u32)>) -> Vec<Standing> {
+    for (driver, (wins, podiums)) in counts {
+        match standings.iter_mut().find(|standing| standing.driver == *driver) {
+            Some(standing) => {
+                standing.wins += wins;
+                standing.podiums += podiums;
+            },
+            None => standings.push(Standing { driver: *driver, points: 0, wins: *wins, podiums: *podiums }),
+        }
+    }
+    standings.sort_by(|a, b| (b.points, b.wins, b.podiums, a.driver).cmp(&(a.points, a.wins, a.podiums, b.driver)));
+    standings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round(order: &[u64], pole: Option<u64>, fastest_lap: Option<u64>) -> Event {
+        let mut event = Event::new("r", "2022-11-05", "Track", "gt1");
+        event.set_results(order, &[], &[]);
+        event.pole = pole;
+        event.fastest_lap = fastest_lap;
+        event
+    }
+
+    #[test]
+    fn f1_points_with_bonuses() {
+        let system = PointsSystem { pole: 1, ..PointsSystem::f1() };
+        let result = standings(&system, &[round(&[1, 2, 3], Some(2), Some(3)), round(&[2, 1], None, None)]);
+        assert_eq!(result.iter().map(|s| (s.driver, s.points, s.wins, s.podiums)).collect::<Vec<_>>(), [(2, 44, 1, 2), (1, 43, 1, 2), (3, 16, 0, 1)]);
+    }
+
+    #[test]
+    fn dropped_rounds_include_missed_ones() {
+        let system = PointsSystem { positions: vec![10, 5], dropped_rounds: 1, ..PointsSystem::f1() };
+        let rounds = [round(&[1, 2], None, None), round(&[2, 1], None, None), round(&[1], None, None)];
+        let result = standings(&system, &rounds);
+        // Driver 1 drops a 5, driver 2 drops the round they missed
+        assert_eq!(result.iter().map(|s| (s.driver, s.points)).collect::<Vec<_>>(), [(1, 20), (2, 15)]);
+    }
+
+    #[test]
+    fn dnf_and_dsq_score_nothing() {
+        let mut event = round(&[1], None, Some(2));
+        event.set_results(&[1], &[2], &[3]);
+        let result = standings(&PointsSystem::f1(), &[event]);
+        assert_eq!(result.iter().map(|s| (s.driver, s.points, s.podiums)).collect::<Vec<_>>(), [(1, 25, 1), (2, 1, 0), (3, 0, 0)]);
+    }
+
+    #[test]
+    fn dsq_loses_bonuses() {
+        let system = PointsSystem { pole: 1, ..PointsSystem::f1() };
+        let mut event = round(&[1, 2], Some(2), Some(2));
+        event.set_results(&[1], &[], &[2]);
+        assert_eq!(system.round_points(&event, 2), 0);
+        let result = standings(&system, &[event]);
+        assert_eq!(result.iter().map(|s| (s.driver, s.points)).collect::<Vec<_>>(), [(1, 25), (2, 0)]);
+    }
+
+    #[test]
+    fn settings() {
+        let mut system = PointsSystem::f1();
+        system.apply("positions=10,6,4").unwrap();
+        system.apply("pole=2").unwrap();
+        system.apply("drop=1").unwrap();
+        assert_eq!(system, PointsSystem { positions: vec![10, 6, 4], pole: 2, fastest_lap: 1, dropped_rounds: 1 });
+        assert!(system.apply("positions=10,x").is_err());
+        assert!(system.apply("laps=3").is_err());
+        system.apply("f1").unwrap();
+        assert_eq!(system, PointsSystem::f1());
+    }
+}
@@ -0,0 +1,87 @@
+use serde::Deserialize;
+
+/// A class cars can be submitted to, as set up in the `[[series]]` tables of the config.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Series {
+    /// Used in commands, e.g. `!submit gt1`.
+    pub id: String,
+    pub name: String,
+    /// The rule file submissions are checked against.
+    pub regulations: String,
+    /// Where cars that pass are saved, one zip per entry.
+    pub registration_dir: String,
+    /// Extra text added to the reply when a car is registered.
+    pub registration_note: Option<String>,
+}
+
+/// The series used when the config doesn't list any.
+pub fn defaults() -> Vec<Series> {
+    vec![
+        Series {
+            id: String::from("group_c"),
+            name: String::from("MCS Season 1 Group C"),
+            regulations: String::from("regulations/mcs_s1_group_c.toml"),
+            registration_dir: String::from("registered/group_c"),
+            registration_note: Some(String::from("**Note:** Please keep in mind that part changes are not checked by me. It'll be done manually by the EMs.")),
+        },
+        Series {
+            id: String::from("gt1"),
+            name: String::from("MCS Season 1 GT1"),
+            regulations: String::from("regulations/mcs_s1_gt1.toml"),
+            registration_dir: String::from("registered/gt1"),
+            registration_note: None,
+        },
+    ]
+}
+
+/// Every series the bot accepts submissions for.
+pub fn all() -> &'static [Series] {
+    &crate::config::get().series
+}
+
+/// Looks up a series by its id, ignoring case.
+pub fn find(id: &str) -> Option<&'static Series> {
+    find_in(all(), id)
+}
+
+fn find_in<'a>(series: &'a [Series], id: &str) -> Option<&'a Series> {
+    series.iter().find(|series| series.id.eq_ignore_ascii_case(id.trim()))
+}
+
+/// Looks up a series a command is tied to, which the config may have left out.
+pub fn configured(id: &str) -> Result<&'static Series, String> {
+    configured_in(all(), id)
+}
+
+fn configured_in<'a>(series: &'a [Series], id: &str) -> Result<&'a Series, String> {
+    find_in(series, id).ok_or_else(|| format!("The `{}` series isn't configured, so there's nothing to submit to!", id))
+}
+
+/// The ids of every series, for telling users what they can pick.
+pub fn ids() -> String {
+    all().iter().map(|series| format!("`{}`", series.id)).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn series_missing_from_the_config_are_reported() {
+        let source = r#"
+            token = "token"
+
+            [[series]]
+            id = "gt3"
+            name = "GT3 Cup"
+            regulations = "regulations/gt3.toml"
+            registration_dir = "registered/gt3"
+        "#;
+        let config = Config::parse(source, |_| None).unwrap();
+        assert_eq!(configured_in(&config.series, "GT3").map(|series| series.name.as_str()), Ok("GT3 Cup"));
+        assert!(configured_in(&config.series, "group_c").unwrap_err().contains("isn't configured"));
+        assert!(configured_in(&config.series, "gt1").is_err());
+    }
+}
@@ -0,0 +1,499 @@
+use serenity::builder::{CreateApplicationCommandOption, CreateEmbed};
+use serenity::framework::standard::{Args, CommandError, Delimiter};
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::application::interaction::application_command::{ApplicationCommandInteraction, CommandDataOptionValue};
+use serenity::model::application::interaction::autocomplete::AutocompleteInteraction;
+use serenity::model::application::interaction::{Interaction, InteractionResponseType};
+use serenity::model::id::GuildId;
+use serenity::model::Permissions;
+use serenity::prelude::*;
+
+use crate::auth::{self, Capability};
+use crate::series::{self, Series};
+use crate::storage::{Data, Store};
+use crate::{deadlines, submission};
+
+/// Discord shows at most this many autocomplete suggestions.
+const MAX_CHOICES: usize = 25;
+
+/// What a slash command answers with.
+enum Reply {
+    Text(String),
+    Embed(CreateEmbed),
+    File { content: String, name: String, bytes: Vec<u8> },
+}
+
+impl From<String> for Reply {
+    fn from(content: String) -> Self {
+        Reply::Text(content)
+    }
+}
+
+/// Registers the slash commands in a guild, replacing the ones registered before. They mirror the
+/// `!` commands of the same name, which keep working alongside them unless they're turned off.
+pub async fn register(ctx: &Context, guild_id: GuildId) -> serenity::Result<()> {
+    guild_id.set_application_commands(&ctx.http, |commands| {
+        commands
+            .create_application_command(|command| {
+                command.name("submit").description("Submit a car for a series")
+                    .create_option(|option| series_option(option, true))
+                    .create_option(|option| option.name("car").description("The zip with your car's export").kind(CommandOptionType::Attachment).required(true))
+                    .create_option(|option| option.name("entry").description("Which of your entries this is a new version of, 1 if not given").kind(CommandOptionType::Integer).min_int_value(1))
+                    .create_option(|option| option.name("csv").description("Which CSV in the zip is the export, by number or name, if there are several").kind(CommandOptionType::String))
+            })
+            .create_application_command(|command| {
+                command.name("my_car").description("Show the cars you have registered for the next round")
+                    .create_option(|option| series_option(option, false))
+            })
+
+            // Results
+            .create_application_command(|command| {
+                command.name("create_leaderboard_post").description("Post a series' leaderboard in this channel")
+                    .create_option(|option| series_option(option, true))
+            })
+            .create_application_command(|command| {
+                command.name("add_event").description("Add an event to a series' leaderboard")
+                    .create_option(|option| series_option(option, true))
+                    .create_option(|option| option.name("event").description("A short id for the event, like r1").kind(CommandOptionType::String).required(true))
+                    .create_option(|option| option.name("date").description("When it's run, as YYYY-MM-DD").kind(CommandOptionType::String).required(true))
+                    .create_option(|option| option.name("track").description("Where it's run").kind(CommandOptionType::String).required(true))
+            })
+            .create_application_command(|command| {
+                command.name("set_result").description("Enter the results of an event")
+                    .create_option(|option| series_option(option, true))
+                    .create_option(event_option)
+                    .create_option(|option| option.name("results").description("Mentions in finishing order, then dnf and dsq ones, e.g. @first @second dnf @third").kind(CommandOptionType::String).required(true))
+                    .create_option(|option| option.name("pole").description("Who got the pole").kind(CommandOptionType::User))
+                    .create_option(|option| option.name("fastest_lap").description("Who got the fastest lap").kind(CommandOptionType::User))
+            })
+            .create_application_command(|command| {
+                command.name("edit_result").description("Change a driver's result in an event")
+                    .create_option(|option| series_option(option, true))
+                    .create_option(event_option)
+                    .create_option(|option| driver_option(option, "Whose result to change"))
+                    .create_option(|option| option.name("result").description("A position, dnf, dsq or remove").kind(CommandOptionType::String).required(true))
+            })
+            .create_application_command(|command| {
+                command.name("remove_win").description("Move an event's winner to P2")
+                    .create_option(|option| series_option(option, true))
+                    .create_option(event_option)
+                    .create_option(|option| driver_option(option, "Whose win to take away"))
+            })
+            .create_application_command(|command| {
+                command.name("remove_podium").description("Move a driver off the podium of an event, to P4")
+                    .create_option(|option| series_option(option, true))
+                    .create_option(event_option)
+                    .create_option(|option| driver_option(option, "Whose podium to take away"))
+            })
+            .create_application_command(|command| {
+                command.name("results").description("List a series' events, or show the results of one")
+                    .create_option(|option| series_option(option, true))
+                    .create_option(|option| option.name("event").description("The event id, all events if not given").kind(CommandOptionType::String))
+            })
+            .create_application_command(|command| {
+                command.name("set_scoring").description("Change how points are scored in a series")
+                    .create_option(|option| series_option(option, true))
+                    .create_option(|option| option.name("settings").description("Like f1, positions=25,18,15 pole=1 fl=1 drop=2").kind(CommandOptionType::String).required(true))
+            })
+            .create_application_command(|command| {
+                command.name("refresh_leaderboard").description("Rewrite the leaderboard posts with the stored standings")
+                    .create_option(|option| series_option(option, false))
+            })
+
+            // Review
+            .create_application_command(|command| {
+                command.name("history").description("Show every version a driver submitted for a series")
+                    .create_option(|option| series_option(option, true))
+                    .create_option(|option| driver_option(option, "Whose submissions to show"))
+            })
+            .create_application_command(|command| {
+                command.name("fetch_submission").description("Get the zip of a submitted version")
+                    .create_option(|option| version_option(option, "version", "The version, see /history"))
+            })
+            .create_application_command(|command| {
+                command.name("diff").description("Show what changed between two versions")
+                    .create_option(|option| version_option(option, "old", "The earlier version, see /history"))
+                    .create_option(|option| version_option(option, "new", "The later version"))
+            })
+            .create_application_command(|command| {
+                command.name("rollback").description("Register an earlier version again")
+                    .create_option(|option| version_option(option, "version", "The version to go back to, see /history"))
+            })
+            .create_application_command(|command| {
+                command.name("round_changes").description("Write what every entry changed between two rounds")
+                    .create_option(|option| series_option(option, true))
+                    .create_option(|option| option.name("from").description("The earlier round, the previous one if not given").kind(CommandOptionType::Integer).min_int_value(1))
+                    .create_option(|option| option.name("to").description("The later round, the current one if not given").kind(CommandOptionType::Integer).min_int_value(1))
+            })
+
+            // Season
+            .create_application_command(|command| {
+                command.name("open_submissions").description("Open submissions for the current round")
+                    .create_option(|option| series_option(option, true))
+            })
+            .create_application_command(|command| {
+                command.name("close_submissions").description("Close submissions for the current round")
+                    .create_option(|option| series_option(option, true))
+            })
+            .create_application_command(|command| {
+                command.name("set_entry_limit").description("Change how many cars a driver may enter")
+                    .create_option(|option| series_option(option, true))
+                    .create_option(|option| option.name("entries").description("Cars per driver").kind(CommandOptionType::Integer).min_int_value(1).required(true))
+            })
+            .create_application_command(|command| {
+                command.name("lock_round").description("Fix the cars of the current round and move on to the next one")
+                    .create_option(|option| series_option(option, true))
+            })
+            .create_application_command(|command| {
+                command.name("season").description("Sum up every round of a series so far")
+                    .create_option(|option| series_option(option, true))
+            })
+            .create_application_command(|command| {
+                command.name("set_deadline").description("Lock the current round automatically, announcing it in this channel")
+                    .create_option(|option| series_option(option, true))
+                    .create_option(|option| option.name("deadline").description("YYYY-MM-DD HH:MM in UTC, or a timestamp").kind(CommandOptionType::String).required(true))
+            })
+            .create_application_command(|command| {
+                command.name("clear_deadline").description("Remove the deadline of the current round")
+                    .create_option(|option| series_option(option, true))
+            })
+            .create_application_command(|command| {
+                command.name("set_reminders").description("Change when reminders are posted before a deadline")
+                    .create_option(|option| series_option(option, true))
+                    .create_option(|option| option.name("hours").description("Hours before the deadline, like 24 1. No reminders if not given").kind(CommandOptionType::String))
+            })
+            .create_application_command(|command| {
+                command.name("grant_role").description("Let a role do something, in one series or every series")
+                    .create_option(capability_option)
+                    .create_option(|option| option.name("role").description("The role").kind(CommandOptionType::Role).required(true))
+                    .create_option(|option| series_option(option, false))
+            })
+            .create_application_command(|command| {
+                command.name("revoke_role").description("Stop letting a role do something")
+                    .create_option(capability_option)
+                    .create_option(|option| option.name("role").description("The role").kind(CommandOptionType::Role).required(true))
+                    .create_option(|option| series_option(option, false))
+            })
+            .create_application_command(|command| command.name("roles").description("Show which roles may do what"))
+    }).await?;
+    Ok(())
+}
+
+fn series_option(option: &mut CreateApplicationCommandOption, required: bool) -> &mut CreateApplicationCommandOption {
+    option.name("series").description("The series").kind(CommandOptionType::String).required(required).set_autocomplete(true)
+}
+
+fn event_option(option: &mut CreateApplicationCommandOption) -> &mut CreateApplicationCommandOption {
+    option.name("event").description("The event id, see /results").kind(CommandOptionType::String).required(true)
+}
+
+fn driver_option<'a>(option: &'a mut CreateApplicationCommandOption, description: &str) -> &'a mut CreateApplicationCommandOption {
+    option.name("driver").description(description).kind(CommandOptionType::User).required(true)
+}
+
+fn version_option<'a>(option: &'a mut CreateApplicationCommandOption, name: &str, description: &str) -> &'a mut CreateApplicationCommandOption {
+    option.name(name).description(description).kind(CommandOptionType::Integer).min_int_value(1).required(true)
+}
+
+fn capability_option(option: &mut CreateApplicationCommandOption) -> &mut CreateApplicationCommandOption {
+    option.name("capability").description("What the role may do").kind(CommandOptionType::String).required(true);
+    for capability in Capability::ALL {
+        option.add_string_choice(capability, capability);
+    }
+    option
+}
+
+/// The capability a command needs, going by the `!` command group it's in.
+fn capability(command: &str) -> Option<Capability> {
+    match command {
+        "create_leaderboard_post" | "add_event" | "set_result" | "edit_result" | "remove_win" | "remove_podium" | "results" | "set_scoring" | "refresh_leaderboard" => Some(Capability::ResultsEditor),
+        "history" | "fetch_submission" | "diff" | "rollback" | "round_changes" => Some(Capability::SubmissionsReviewer),
+        "open_submissions" | "close_submissions" | "set_entry_limit" | "lock_round" | "season" | "set_deadline" | "clear_deadline" | "set_reminders" | "grant_role" | "revoke_role" | "roles" => Some(Capability::SeasonAdmin),
+        _ => None,
+    }
+}
+
+/// Answers a slash command or suggests series for one that's being typed.
+pub async fn handle(ctx: &Context, interaction: Interaction) {
+    let handled = match interaction {
+        Interaction::ApplicationCommand(command) => run(ctx, &command).await,
+        Interaction::Autocomplete(autocomplete) => suggest_series(ctx, &autocomplete).await,
+        _ => Ok(()),
+    };
+    if let Err(e) = handled {
+        println!("Failed to handle an interaction: {:?}", e);
+    }
+}
+
+async fn run(ctx: &Context, command: &ApplicationCommandInteraction) -> Result<(), CommandError> {
+    // Checking a car takes longer than Discord waits for an answer, so it's sent once it's ready
+    command.create_interaction_response(&ctx.http, |response| response.kind(InteractionResponseType::DeferredChannelMessageWithSource)).await?;
+    let reply = match reply(ctx, command).await {
+        Ok(reply) => reply,
+        Err(e) => {
+            println!("Failed to run /{}: {:?}", command.data.name, e);
+            Reply::Text(String::from("Something went wrong! Please ping any of the EMs."))
+        },
+    };
+    match reply {
+        Reply::Text(content) => {
+            command.edit_original_interaction_response(&ctx.http, |response| response.content(content).allowed_mentions(|mentions| mentions.empty_users().empty_roles())).await?;
+        },
+        Reply::Embed(embed) => {
+            command.edit_original_interaction_response(&ctx.http, |response| response.set_embed(embed)).await?;
+        },
+        // Files can't be added by editing the deferred response, the first followup replaces it instead
+        Reply::File { content, name, bytes } => {
+            command.create_followup_message(&ctx.http, |message| message.content(content).add_file((bytes.as_slice(), name.as_str())).allowed_mentions(|mentions| mentions.empty_users())).await?;
+        },
+    }
+    Ok(())
+}
+
+async fn reply(ctx: &Context, command: &ApplicationCommandInteraction) -> Result<Reply, CommandError> {
+    let user = *command.user.id.as_u64();
+    let series = string(command, "series").and_then(series::find);
+    let unknown_series = || Reply::Text(format!("Pick one of {} as the series!", series::ids()));
+    if let Some(capability) = capability(&command.data.name) {
+        if let Err(reason) = authorize(ctx, command, capability).await {
+            return Ok(reason.into());
+        }
+    }
+    match command.data.name.as_str() {
+        "submit" => {
+            let (series, car) = match (series, value(command, "car")) {
+                (Some(series), Some(CommandDataOptionValue::Attachment(car))) => (series, car),
+                _ => return Ok(unknown_series()),
+            };
+            let entry = match integer(command, "entry").map(u32::try_from) {
+                None => 1,
+                Some(Ok(entry)) if entry > 0 => entry,
+                Some(_) => return Ok(Reply::Text(String::from("The entry counts from 1, like `2` for your second car!"))),
+            };
+            Ok(submission::submit(ctx, user, std::slice::from_ref(car), series, entry, string(command, "csv")).await?.into())
+        },
+        "my_car" => Ok(Store::get(ctx).await.read(|data| crate::my_cars(data, user, string(command, "series").unwrap_or_default())).await.into()),
+        "history" => match (series, self::user(command, "driver")) {
+            (Some(series), Some(driver)) => Ok(Store::get(ctx).await.read(|data| crate::history_of(data, series, driver)).await.into()),
+            _ => Ok(unknown_series()),
+        },
+        "fetch_submission" => {
+            let version = integer(command, "version").and_then(|version| u64::try_from(version).ok()).unwrap_or_default();
+            let (submission, file) = match Store::get(ctx).await.read(|data| crate::kept_file(data, version)).await {
+                Ok(kept) => kept,
+                Err(reply) => return Ok(reply.into()),
+            };
+            let bytes = std::fs::read(&file)?;
+            Ok(Reply::File { content: format!("Version #{} of <@{}>", submission.id, submission.driver), name: submission.filename, bytes })
+        },
+        "diff" => {
+            let version = |name| integer(command, name).and_then(|version| u64::try_from(version).ok()).unwrap_or_default();
+            match crate::compare_versions(ctx, version("old"), version("new")).await {
+                Ok(embed) => Ok(Reply::Embed(embed)),
+                Err(reply) => Ok(reply.into()),
+            }
+        },
+        "rollback" => {
+            let version = integer(command, "version").and_then(|version| u64::try_from(version).ok()).unwrap_or_default();
+            update(ctx, |data| crate::roll_back_to(data, version, submission::now())).await
+        },
+        "round_changes" => match series {
+            Some(series) => {
+                let round = |name| integer(command, name).and_then(|round| u32::try_from(round).ok());
+                match crate::compare_rounds(ctx, series, round("from"), round("to")).await? {
+                    (content, Some((name, report))) => Ok(Reply::File { content, name, bytes: report.into_bytes() }),
+                    (content, None) => Ok(content.into()),
+                }
+            },
+            None => Ok(unknown_series()),
+        },
+        "grant_role" | "revoke_role" => {
+            let capability = string(command, "capability").unwrap_or_default().parse::<Capability>();
+            let role = match value(command, "role") {
+                Some(CommandDataOptionValue::Role(role)) => *role.id.as_u64(),
+                _ => return Ok(Reply::Text(String::from("Pick the role!"))),
+            };
+            let capability = match capability {
+                Ok(capability) => capability,
+                Err(reply) => return Ok(reply.into()),
+            };
+            let series_id = match (string(command, "series"), series) {
+                (None, _) => None,
+                (Some(_), Some(series)) => Some(series.id.as_str()),
+                (Some(_), None) => return Ok(Reply::Text(format!("Pick one of {} as the series, or none for every series!", series::ids()))),
+            };
+            let grant = command.data.name == "grant_role";
+            let guild_id = command.guild_id.map(|guild_id| *guild_id.as_u64()).unwrap_or_default();
+            update(ctx, |data| crate::update_role(data, guild_id, capability, role, series_id, grant)).await
+        },
+        "roles" => {
+            let guild_id = command.guild_id.map(|guild_id| *guild_id.as_u64()).unwrap_or_default();
+            Ok(Store::get(ctx).await.read(|data| crate::roles_of(data, guild_id)).await.into())
+        },
+        name => match series {
+            Some(series) if capability(name) == Some(Capability::SeasonAdmin) => season(ctx, command, series).await,
+            _ if capability(name) == Some(Capability::ResultsEditor) => results(ctx, command).await,
+            None if capability(name) == Some(Capability::SeasonAdmin) => Ok(unknown_series()),
+            _ => Ok(Reply::Text(format!("I don't know `/{}`!", name))),
+        },
+    }
+}
+
+/// Answers the commands about a series' leaderboard.
+async fn results(ctx: &Context, command: &ApplicationCommandInteraction) -> Result<Reply, CommandError> {
+    let guild_id = command.guild_id.map(|guild_id| *guild_id.as_u64());
+    let series_id = string(command, "series").unwrap_or_default();
+    if command.data.name == "refresh_leaderboard" {
+        return match guild_id {
+            Some(guild_id) => Ok(format!("Refreshed {} leaderboard post(s).", crate::refresh_posts(ctx, guild_id, series_id).await?).into()),
+            None => Ok(Reply::Text(String::from("Leaderboards only exist in servers!"))),
+        };
+    }
+    let must_exist = command.data.name != "create_leaderboard_post";
+    let (guild_id, series_id) = match Store::get(ctx).await.read(|data| crate::leaderboard_of(data, guild_id, series_id, must_exist)).await {
+        Ok(found) => found,
+        Err(reply) => return Ok(reply.into()),
+    };
+    let event_id = string(command, "event").unwrap_or_default().trim();
+    let driver = self::user(command, "driver");
+    match command.data.name.as_str() {
+        "create_leaderboard_post" => {
+            crate::post_leaderboard(ctx, guild_id, &series_id, command.channel_id).await?;
+            Ok(format!("Posted the {} leaderboard, it's kept up to date from now on.", crate::series_title(&series_id)).into())
+        },
+        "add_event" => {
+            let date = string(command, "date").unwrap_or_default().trim();
+            let track = string(command, "track").unwrap_or_default().trim();
+            if !crate::results::valid_date(date) {
+                return Ok(format!("`{}` isn't a date, write it as YYYY-MM-DD!", date).into());
+            }
+            update(ctx, |data| crate::new_event(data, guild_id, &series_id, event_id, date, track)).await
+        },
+        "set_result" => {
+            let results = string(command, "results").unwrap_or_default();
+            let mut classification = match crate::parse_results(&mut Args::new(results, &[Delimiter::Single(' ')])) {
+                Ok(classification) => classification,
+                Err(reply) => return Ok(reply.into()),
+            };
+            classification.pole = self::user(command, "pole").or(classification.pole);
+            classification.fastest_lap = self::user(command, "fastest_lap").or(classification.fastest_lap);
+            Ok(crate::update_event(ctx, guild_id, &series_id, event_id, |event| crate::apply_results(event, classification)).await?.into())
+        },
+        "edit_result" => {
+            let change = string(command, "result").unwrap_or_default();
+            match driver {
+                Some(driver) => Ok(crate::update_event(ctx, guild_id, &series_id, event_id, |event| crate::place_driver(event, driver, change)).await?.into()),
+                None => Ok(Reply::Text(String::from("Pick the driver whose result to change!"))),
+            }
+        },
+        "remove_win" | "remove_podium" => {
+            let (positions, what) = if command.data.name == "remove_win" { (1, "win") } else { (3, "podium") };
+            let drivers: Vec<u64> = driver.into_iter().collect();
+            Ok(crate::update_event(ctx, guild_id, &series_id, event_id, |event| crate::remove_top_finishers(event, &drivers, positions, what)).await?.into())
+        },
+        "results" => Ok(Store::get(ctx).await.read(|data| crate::results_of(data, guild_id, &series_id, event_id)).await.into()),
+        "set_scoring" => {
+            let settings: Vec<String> = string(command, "settings").unwrap_or_default().split_whitespace().map(String::from).collect();
+            Ok(crate::change_scoring(ctx, guild_id, &series_id, &settings).await?.into())
+        },
+        name => Ok(format!("I don't know `/{}`!", name).into()),
+    }
+}
+
+/// Answers the commands about a series' submissions and rounds.
+async fn season(ctx: &Context, command: &ApplicationCommandInteraction, series: &Series) -> Result<Reply, CommandError> {
+    match command.data.name.as_str() {
+        "open_submissions" | "close_submissions" => {
+            let open = command.data.name == "open_submissions";
+            update(ctx, |data| Ok(crate::set_series_open(data, series, open))).await
+        },
+        "set_entry_limit" => match integer(command, "entries").and_then(|entries| u32::try_from(entries).ok()).filter(|entries| *entries > 0) {
+            Some(max_entries) => update(ctx, |data| Ok(crate::set_series_entry_limit(data, series, max_entries))).await,
+            None => Ok(Reply::Text(String::from("Drivers have to be allowed at least 1 entry!"))),
+        },
+        "lock_round" => update(ctx, |data| Ok(crate::lock_series_round(data, series, submission::now()))).await,
+        "season" => Ok(Store::get(ctx).await.read(|data| crate::season_of(data, series)).await.into()),
+        "set_deadline" => match deadlines::parse_deadline(string(command, "deadline").unwrap_or_default()) {
+            Some(deadline) => {
+                let channel = *command.channel_id.as_u64();
+                update(ctx, |data| crate::set_series_deadline(data, series, deadline, channel, submission::now())).await
+            },
+            None => Ok(Reply::Text(String::from("Write the deadline as YYYY-MM-DD HH:MM, in UTC, or as a timestamp!"))),
+        },
+        "clear_deadline" => update(ctx, |data| Ok(crate::clear_series_deadline(data, series))).await,
+        "set_reminders" => {
+            let hours: Result<Vec<u64>, _> = string(command, "hours").unwrap_or_default().split_whitespace().map(|hours| hours.trim_end_matches('h').parse::<u64>()).collect();
+            match hours {
+                Ok(hours) => update(ctx, |data| Ok(crate::set_series_reminders(data, series, &hours))).await,
+                Err(_) => Ok(Reply::Text(String::from("Give the hours before the deadline as whole numbers, like `24 1`!"))),
+            }
+        },
+        name => Ok(format!("I don't know `/{}`!", name).into()),
+    }
+}
+
+/// Runs `update` on the stored data and replies with what it returns.
+async fn update<F: FnOnce(&mut Data) -> Result<String, String>>(ctx: &Context, update: F) -> Result<Reply, CommandError> {
+    let (Ok(reply) | Err(reply)) = Store::get(ctx).await.update(update).await?;
+    Ok(reply.into())
+}
+
+/// Checks the member running a command may use `capability` in every series it's about, going by
+/// the roles granted in their guild.
+async fn authorize(ctx: &Context, command: &ApplicationCommandInteraction, capability: Capability) -> Result<(), String> {
+    let (guild_id, member) = match (command.guild_id, &command.member) {
+        (Some(guild_id), Some(member)) => (*guild_id.as_u64(), auth::Member {
+            roles: member.roles.iter().map(|role| *role.as_u64()).collect(),
+            permissions: member.permissions.unwrap_or_else(Permissions::empty),
+        }),
+        _ => return Err(String::from("This command only works in servers!")),
+    };
+    let mut scopes: Vec<String> = string(command, "series").map(String::from).into_iter().collect();
+    scopes.extend(["version", "old", "new"].into_iter().filter_map(|name| integer(command, name)).map(|version| format!("#{}", version)));
+    let scopes: Vec<&str> = scopes.iter().map(String::as_str).collect();
+    Store::get(ctx).await.read(|data| auth::authorize_scopes(data, guild_id, &member, capability, &scopes)).await
+}
+
+fn value<'a>(command: &'a ApplicationCommandInteraction, name: &str) -> Option<&'a CommandDataOptionValue> {
+    command.data.options.iter().find(|option| option.name == name).and_then(|option| option.resolved.as_ref())
+}
+
+fn string<'a>(command: &'a ApplicationCommandInteraction, name: &str) -> Option<&'a str> {
+    match value(command, name) {
+        Some(CommandDataOptionValue::String(value)) => Some(value.as_str()),
+        _ => None,
+    }
+}
+
+fn integer(command: &ApplicationCommandInteraction, name: &str) -> Option<i64> {
+    match value(command, name) {
+        Some(CommandDataOptionValue::Integer(value)) => Some(*value),
+        _ => None,
+    }
+}
+
+fn user(command: &ApplicationCommandInteraction, name: &str) -> Option<u64> {
+    match value(command, name) {
+        Some(CommandDataOptionValue::User(user, _)) => Some(*user.id.as_u64()),
+        _ => None,
+    }
+}
+
+/// Suggests the series whose id or name contains what's been typed so far, along with any older
+/// leaderboards in the guild that aren't in the series table.
+async fn suggest_series(ctx: &Context, autocomplete: &AutocompleteInteraction) -> Result<(), CommandError> {
+    let typed = autocomplete.data.options.iter().find(|option| option.focused).and_then(|option| option.value.as_ref()).and_then(|value| value.as_str()).unwrap_or_default().to_lowercase();
+    let mut choices: Vec<(String, String)> = series::all().iter().map(|series| (series.name.clone(), series.id.clone())).collect();
+    if let Some(guild_id) = autocomplete.guild_id {
+        let leaderboards: Vec<String> = Store::get(ctx).await.read(|data| data.leaderboards.guilds.get(guild_id.as_u64()).map(|guild| guild.keys().cloned().collect()).unwrap_or_default()).await;
+        choices.extend(leaderboards.into_iter().filter(|id| series::find(id).is_none()).map(|id| (id.clone(), id)));
+    }
+    choices.retain(|(name, id)| name.to_lowercase().contains(&typed) || id.contains(&typed));
+    autocomplete.create_autocomplete_response(&ctx.http, |response| {
+        for (name, id) in choices.iter().take(MAX_CHOICES) {
+            response.add_string_choice(name, id);
+        }
+        response
+    }).await?;
+    Ok(())
+}
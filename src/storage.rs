@@ -0,0 +1,507 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use serenity::framework::standard::CommandError;
+use serenity::http::Http;
+use serenity::prelude::{Context, TypeMapKey};
+use tokio::sync::Mutex;
+
+use crate::auth::Roles;
+use crate::leaderboard::{Leaderboard, Leaderboards};
+
+/// Bumped whenever `Data` changes shape, with a step in `migrate` that upgrades the version before.
+pub const SCHEMA_VERSION: u64 = 2;
+
+/// The series a leaderboard from before they were split per series is kept under.
+pub const LEGACY_SERIES: &str = "legacy";
+
+/// Everything the bot keeps between restarts.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct Data {
+    pub schema_version: u64,
+    pub leaderboards: Leaderboards,
+    /// Every submission ever made, oldest first. Never edited, only appended to.
+    pub submissions: Vec<Submission>,
+    /// Settings per series id.
+    pub series: BTreeMap<String, SeriesSettings>,
+    /// The cars every locked round was run with, oldest first.
+    pub rounds: Vec<RoundSnapshot>,
+    /// Which roles may use which commands, per guild id.
+    pub roles: BTreeMap<u64, Roles>,
+}
+
+/// One submitted version of a driver's car.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Submission {
+    /// Version number, unique across every series.
+    pub id: u64,
+    pub series: String,
+    /// The round of the series this version was submitted for.
+    pub round: u32,
+    /// The user who submitted it, and owns the entry.
+    pub driver: u64,
+    /// Which of the driver's entries in the series this is a version of, starting at 1.
+    pub entry: u32,
+    /// The trim that was entered, as in "1. Model Trim (Variant)". Empty for submissions from
+    /// before it was recorded.
+    pub car: String,
+    /// Name of the attached zip.
+    pub filename: String,
+    /// Path of the export inside the zip. Empty for submissions from before it was recorded,
+    /// which had a single CSV.
+    pub csv: String,
+    /// SHA-256 of the zip, in hex. Empty for submissions from before hashes were recorded.
+    pub hash: String,
+    /// Where the zip is kept, if it was accepted.
+    pub file: Option<String>,
+    /// Seconds since the Unix epoch.
+    pub submitted_at: u64,
+    pub verdict: Verdict,
+    /// The version this one was rolled back to, if it was made by a rollback.
+    pub restored_from: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Passed,
+    Rejected { issues: Vec<String> },
+}
+
+/// The cars entered for a round, fixed when the round is locked.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RoundSnapshot {
+    pub series: String,
+    pub round: u32,
+    /// Seconds since the Unix epoch.
+    pub locked_at: u64,
+    /// Sorted by driver and entry.
+    pub cars: Vec<EnteredCar>,
+}
+
+/// The version of an entry that counts for a round.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EnteredCar {
+    pub driver: u64,
+    pub entry: u32,
+    pub submission: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct SeriesSettings {
+    pub submissions_open: bool,
+    /// The round submissions currently count for. Every round before it is locked.
+    pub round: u32,
+    /// How many cars a single user may enter.
+    pub max_entries: u32,
+    /// When submissions for the current round close, in seconds since the Unix epoch. The round
+    /// is locked automatically once it has passed.
+    pub deadline: Option<u64>,
+    /// How long before the deadline reminders are posted, in seconds.
+    pub reminders: Vec<u64>,
+    /// The reminders that have been posted for the current deadline.
+    pub reminders_sent: Vec<u64>,
+    /// Where reminders and the automatic lock are posted.
+    pub announcement_channel: Option<u64>,
+}
+
+impl Default for SeriesSettings {
+    fn default() -> Self {
+        Self {
+            submissions_open: true,
+            round: 1,
+            max_entries: 1,
+            deadline: None,
+            reminders: vec![24 * 60 * 60, 60 * 60],
+            reminders_sent: Vec::new(),
+            announcement_channel: None,
+        }
+    }
+}
+
+impl Data {
+    pub fn series_settings(&self, series_id: &str) -> SeriesSettings {
+        self.series.get(series_id).cloned().unwrap_or_default()
+    }
+
+    /// Records a submission, filling in its id.
+    pub fn add_submission(&mut self, mut submission: Submission) -> u64 {
+        submission.id = self.submissions.last().map_or(1, |last| last.id + 1);
+        let id = submission.id;
+        self.submissions.push(submission);
+        id
+    }
+
+    pub fn submission(&self, id: u64) -> Option<&Submission> {
+        self.submissions.iter().find(|submission| submission.id == id)
+    }
+
+    /// Every version a driver submitted for a series, oldest first.
+    pub fn history(&self, series_id: &str, driver: u64) -> impl Iterator<Item = &Submission> {
+        let series_id = String::from(series_id);
+        self.submissions.iter().filter(move |submission| submission.series == series_id && submission.driver == driver)
+    }
+
+    /// The version of an entry that counts for a round: the last one that passed.
+    pub fn current(&self, series_id: &str, round: u32, driver: u64, entry: u32) -> Option<&Submission> {
+        self.history(series_id, driver).filter(|submission| submission.round == round && submission.entry == entry && submission.verdict == Verdict::Passed).last()
+    }
+
+    /// Every entry with a car registered for a round, as `(driver, entry)`.
+    pub fn entries(&self, series_id: &str, round: u32) -> Vec<(u64, u32)> {
+        let mut entries: Vec<(u64, u32)> = self.submissions.iter()
+            .filter(|submission| submission.series == series_id && submission.round == round && submission.verdict == Verdict::Passed)
+            .map(|submission| (submission.driver, submission.entry))
+            .collect();
+        entries.sort_unstable();
+        entries.dedup();
+        entries
+    }
+
+    /// The cars a driver has registered for a round, one per entry.
+    pub fn registered(&self, series_id: &str, round: u32, driver: u64) -> Vec<&Submission> {
+        self.cars(series_id, round).into_iter()
+            .filter(|car| car.driver == driver)
+            .filter_map(|car| self.submission(car.submission))
+            .collect()
+    }
+
+    pub fn locked(&self, series_id: &str, round: u32) -> Option<&RoundSnapshot> {
+        self.rounds.iter().find(|snapshot| snapshot.series == series_id && snapshot.round == round)
+    }
+
+    /// The cars entered for a round. For a locked round that's its snapshot. Otherwise it's every
+    /// car from the round before, with the entries that were resubmitted since replaced.
+    pub fn cars(&self, series_id: &str, round: u32) -> Vec<EnteredCar> {
+        if let Some(snapshot) = self.locked(series_id, round) {
+            return snapshot.cars.clone();
+        }
+        let mut cars: BTreeMap<(u64, u32), u64> = match round {
+            0 | 1 => BTreeMap::new(),
+            _ => self.cars(series_id, round - 1).into_iter().map(|car| ((car.driver, car.entry), car.submission)).collect(),
+        };
+        for (driver, entry) in self.entries(series_id, round) {
+            if let Some(current) = self.current(series_id, round, driver, entry) {
+                cars.insert((driver, entry), current.id);
+            }
+        }
+        cars.into_iter().map(|((driver, entry), submission)| EnteredCar { driver, entry, submission }).collect()
+    }
+
+    /// Fixes the cars of the series' current round and moves on to the next one, with
+    /// submissions closed and no deadline until they're set for it.
+    pub fn lock_round(&mut self, series_id: &str, locked_at: u64) -> RoundSnapshot {
+        let round = self.series_settings(series_id).round;
+        let snapshot = RoundSnapshot { series: String::from(series_id), round, locked_at, cars: self.cars(series_id, round) };
+        self.rounds.push(snapshot.clone());
+        let settings = self.series.entry(String::from(series_id)).or_default();
+        settings.round = round + 1;
+        settings.submissions_open = false;
+        settings.deadline = None;
+        settings.reminders_sent.clear();
+        snapshot
+    }
+
+    /// Makes an older version a driver's current one again for the round it was submitted for,
+    /// by recording it as a new version. Returns the new version's id.
+    pub fn roll_back(&mut self, id: u64, submitted_at: u64) -> Result<u64, String> {
+        let old = self.submission(id).ok_or_else(|| format!("There's no version #{}!", id))?;
+        if old.verdict != Verdict::Passed {
+            return Err(format!("Version #{} didn't pass the checks, it can't be rolled back to!", id));
+        }
+        if self.locked(&old.series, old.round).is_some() {
+            return Err(format!("Version #{} is from round {}, which is locked!", id, old.round));
+        }
+        if self.current(&old.series, old.round, old.driver, old.entry).map(|current| current.hash == old.hash) == Some(true) {
+            return Err(format!("Version #{} already is the current one!", id));
+        }
+        let restored = Submission { submitted_at, restored_from: Some(id), ..old.clone() };
+        Ok(self.add_submission(restored))
+    }
+}
+
+/// Where the bot's data is persisted.
+pub trait Storage: Send + Sync {
+    /// The stored data as JSON, or `None` if nothing has been stored yet.
+    fn load(&self) -> Result<Option<Value>, CommandError>;
+    /// Replaces the stored data. Must either fully succeed or leave the old data in place.
+    fn save(&self, data: &Value) -> Result<(), CommandError>;
+}
+
+/// Keeps everything in a single JSON file. Writes go to a temporary file that is then renamed
+/// over the old one, so a crash mid-write never leaves a half-written file behind.
+pub struct JsonFile {
+    path: PathBuf,
+}
+
+impl JsonFile {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl Storage for JsonFile {
+    fn load(&self) -> Result<Option<Value>, CommandError> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(source) => Ok(Some(serde_json::from_str(&source)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, data: &Value) -> Result<(), CommandError> {
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let mut file = std::fs::File::create(&tmp_path)?;
+        file.write_all(serde_json::to_string_pretty(data)?.as_bytes())?;
+        file.sync_all()?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Upgrades stored data to `SCHEMA_VERSION`, one version at a time. Everything from before the
+/// store existed comes from the old leaderboard file, which `Store::open` imports.
+fn migrate(mut value: Value) -> Result<Value, CommandError> {
+    loop {
+        let version = value.get("schema_version").and_then(Value::as_u64).unwrap_or(0);
+        value = match version {
+            SCHEMA_VERSION => return Ok(value),
+            1 => v1_to_v2(value),
+            _ => return Err(format!("Stored data has schema version {}, but this bot only knows up to {}", version, SCHEMA_VERSION).into()),
+        };
+    }
+}
+
+/// Roles used to be shared by every guild. Role ids belong to a single guild, but which one isn't
+/// stored, so they're only kept if every leaderboard is in the same guild.
+fn v1_to_v2(mut value: Value) -> Value {
+    let guilds: Vec<String> = value.pointer("/leaderboards/guilds").and_then(Value::as_object).map(|guilds| guilds.keys().cloned().collect()).unwrap_or_default();
+    let roles = value.get_mut("roles").map(Value::take).unwrap_or(Value::Null);
+    let mut per_guild = serde_json::Map::new();
+    match guilds.as_slice() {
+        [guild_id] if !roles.is_null() => {
+            per_guild.insert(guild_id.clone(), roles);
+        },
+        _ if roles.is_null() => {},
+        _ => println!("Dropped the granted roles, as it isn't known which server they're from. Grant them again where they're needed."),
+    }
+    value["roles"] = Value::Object(per_guild);
+    value["schema_version"] = Value::from(2);
+    value
+}
+
+/// The bot's data, shared between commands. Updates are serialised and either fully saved or not applied at all.
+pub struct Store {
+    backend: Box<dyn Storage>,
+    data: Mutex<Data>,
+}
+
+impl TypeMapKey for Store {
+    type Value = Arc<Store>;
+}
+
+impl Store {
+    /// Loads and migrates the stored data. An empty store is filled from the old leaderboard file at
+    /// `legacy_leaderboard`.
+    pub async fn open(backend: Box<dyn Storage>, legacy_leaderboard: &Path, http: &Http) -> Result<Self, CommandError> {
+        let data = match backend.load()? {
+            Some(value) => {
+                let migrated = migrate(value.clone())?;
+                let data: Data = serde_json::from_value(migrated.clone())?;
+                if migrated != value {
+                    println!("Migrated stored data to schema version {}", SCHEMA_VERSION);
+                    backend.save(&migrated)?;
+                }
+                data
+            },
+            None => {
+                let mut data = Data { schema_version: SCHEMA_VERSION, ..Data::default() };
+                import_legacy_leaderboard(&mut data, legacy_leaderboard, http).await?;
+                backend.save(&serde_json::to_value(&data)?)?;
+                data
+            },
+        };
+        Ok(Self { backend, data: Mutex::new(data) })
+    }
+
+    /// The store registered with the client.
+    pub async fn get(ctx: &Context) -> Arc<Store> {
+        ctx.data.read().await.get::<Store>().expect("Store wasn't registered with the client").clone()
+    }
+
+    pub async fn read<R, F: FnOnce(&Data) -> R>(&self, read: F) -> R {
+        read(&*self.data.lock().await)
+    }
+
+    /// Runs `update` on a copy of the data and saves it. The change is only kept if `update`
+    /// returns `Ok` and the save succeeds; `Err(reason)` is handed back without touching anything.
+    pub async fn update<R, F: FnOnce(&mut Data) -> Result<R, String>>(&self, update: F) -> Result<Result<R, String>, CommandError> {
+        let mut data = self.data.lock().await;
+        let mut changed = data.clone();
+        let result = match update(&mut changed) {
+            Ok(result) => result,
+            Err(reason) => return Ok(Err(reason)),
+        };
+        self.backend.save(&serde_json::to_value(&changed)?)?;
+        *data = changed;
+        Ok(Ok(result))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LegacyLeaderboardFile {
+    PerGuild(Leaderboards),
+    /// A single leaderboard, from before they were split by guild and series.
+    Single(Leaderboard),
+}
+
+/// Imports the old leaderboard file. A single old leaderboard is moved into the guild its post is
+/// in. If that can't be looked up it's left out, rather than keeping the bot from starting.
+async fn import_legacy_leaderboard(data: &mut Data, path: &Path, http: &Http) -> Result<(), CommandError> {
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    match serde_json::from_str(&source)? {
+        LegacyLeaderboardFile::PerGuild(leaderboards) => data.leaderboards = leaderboards,
+        LegacyLeaderboardFile::Single(leaderboard) => {
+            let guild_id = match http.get_channel(leaderboard.channel_id).await {
+                Ok(channel) => channel.guild().map(|channel| *channel.guild_id.as_u64()),
+                Err(e) => {
+                    println!("Failed to look up the channel of the old leaderboard post: {:?}", e);
+                    None
+                },
+            };
+            match guild_id {
+                Some(guild_id) => {
+                    data.leaderboards.guild(guild_id).insert(String::from(LEGACY_SERIES), leaderboard);
+                },
+                None => {
+                    println!("Skipped the old leaderboard in {}, as the server its post is in isn't known. It's still in that file.", path.display());
+                    return Ok(());
+                },
+            }
+        },
+    }
+    println!("Imported {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_unknown_schema_versions() {
+        assert!(migrate(serde_json::json!({ "schema_version": SCHEMA_VERSION })).is_ok());
+        assert!(migrate(serde_json::json!({ "leaderboards": { "guilds": {} } })).is_err());
+        assert!(migrate(serde_json::json!({ "schema_version": SCHEMA_VERSION + 1 })).is_err());
+    }
+
+    #[test]
+    fn roles_are_moved_into_their_guild() {
+        let roles = serde_json::json!({ "global": { "results_editor": [1] }, "series": {} });
+        let single = serde_json::json!({ "schema_version": 1, "leaderboards": { "guilds": { "5": {} } }, "roles": roles });
+        let migrated = migrate(single).unwrap();
+        assert_eq!(migrated["schema_version"], SCHEMA_VERSION);
+        let data: Data = serde_json::from_value(migrated).unwrap();
+        assert!(data.roles[&5].global[&crate::auth::Capability::ResultsEditor].contains(&1));
+
+        // With several guilds it isn't known whose roles they are
+        let several = serde_json::json!({ "schema_version": 1, "leaderboards": { "guilds": { "5": {}, "6": {} } }, "roles": roles });
+        let data: Data = serde_json::from_value(migrate(several).unwrap()).unwrap();
+        assert!(data.roles.is_empty());
+    }
+
+    fn submission(driver: u64, hash: &str, verdict: Verdict) -> Submission {
+        Submission {
+            id: 0,
+            series: String::from("gt1"),
+            round: 1,
+            driver,
+            entry: 1,
+            car: String::from("1. Model Trim (Variant)"),
+            filename: String::from("car.zip"),
+            csv: String::from("Car/Car.csv"),
+            hash: String::from(hash),
+            file: Some(format!("registered/gt1/{}.zip", hash)),
+            submitted_at: 0,
+            verdict,
+            restored_from: None,
+        }
+    }
+
+    #[test]
+    fn history_and_rollback() {
+        let mut data = Data::default();
+        let first = data.add_submission(submission(1, "a", Verdict::Passed));
+        data.add_submission(submission(2, "b", Verdict::Passed));
+        data.add_submission(submission(1, "c", Verdict::Passed));
+        let rejected = data.add_submission(submission(1, "d", Verdict::Rejected { issues: vec![String::from("too fast")] }));
+        assert_eq!(data.current("gt1", 1, 1, 1).unwrap().hash, "c");
+        assert_eq!(data.history("gt1", 1).count(), 3);
+        assert_eq!(data.entries("gt1", 1), vec![(1, 1), (2, 1)]);
+
+        assert!(data.roll_back(rejected, 0).is_err());
+        let restored = data.roll_back(first, 5).unwrap();
+        assert_eq!(data.current("gt1", 1, 1, 1).unwrap().id, restored);
+        assert_eq!(data.current("gt1", 1, 1, 1).unwrap().restored_from, Some(first));
+        assert!(data.roll_back(first, 6).is_err());
+    }
+
+    #[test]
+    fn entries_are_kept_apart() {
+        let mut data = Data::default();
+        data.add_submission(submission(1, "a", Verdict::Passed));
+        data.add_submission(Submission { entry: 2, ..submission(1, "b", Verdict::Passed) });
+        data.add_submission(submission(1, "c", Verdict::Passed));
+        let registered: Vec<&str> = data.registered("gt1", 1, 1).iter().map(|submission| submission.hash.as_str()).collect();
+        assert_eq!(registered, vec!["c", "b"]);
+        assert!(data.registered("gt1", 1, 2).is_empty());
+    }
+
+    #[test]
+    fn locked_rounds() {
+        let mut data = Data::default();
+        let first = data.add_submission(submission(1, "a", Verdict::Passed));
+        data.add_submission(submission(2, "b", Verdict::Passed));
+        let snapshot = data.lock_round("gt1", 10);
+        assert_eq!(snapshot.round, 1);
+        assert_eq!(snapshot.cars.len(), 2);
+        assert_eq!(data.series_settings("gt1").round, 2);
+        assert!(!data.series_settings("gt1").submissions_open);
+        assert!(data.roll_back(first, 11).is_err());
+
+        // Cars that aren't resubmitted carry over to the next round
+        let updated = data.add_submission(Submission { round: 2, ..submission(1, "c", Verdict::Passed) });
+        let cars = data.cars("gt1", 2);
+        assert_eq!(cars.iter().map(|car| car.submission).collect::<Vec<_>>(), vec![updated, 2]);
+        assert_eq!(data.registered("gt1", 2, 2)[0].hash, "b");
+        assert_eq!(data.cars("gt1", 1), snapshot.cars);
+
+        // Submissions made after a round was locked don't change its snapshot
+        data.add_submission(submission(3, "d", Verdict::Passed));
+        assert_eq!(data.cars("gt1", 1), snapshot.cars);
+        data.lock_round("gt1", 20);
+        assert_eq!(data.locked("gt1", 2).unwrap().cars, cars);
+    }
+
+    #[test]
+    fn json_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("rnr_storage_test_{}.json", std::process::id()));
+        let file = JsonFile::new(&path);
+        assert!(file.load().unwrap().is_none());
+        file.save(&serde_json::json!({ "schema_version": 1 })).unwrap();
+        assert_eq!(file.load().unwrap().unwrap()["schema_version"], 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
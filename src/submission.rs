@@ -0,0 +1,178 @@
+use car_checker::regulations::Regulations;
+use car_checker::regulations::rules::RuleSet;
+use car_checker::report::Report;
+use car_checker::archive::{self, Candidate, Limits};
+use car_checker::{ArchiveError, CarData, Export, ExportError};
+use sha2::{Digest, Sha256};
+use serenity::prelude::*;
+use serenity::model::channel::Attachment;
+use serenity::framework::standard::CommandError;
+
+use crate::config;
+use crate::series::Series;
+use crate::storage::{Store, Submission, Verdict};
+use crate::workspace::Workspace;
+
+/// Formats every violation in a failed check for a Discord reply, staying under the message limit.
+fn format_violations(report: &Report) -> String {
+    let mut list = String::new();
+    let violations = report.violations();
+    for (i, violation) in violations.iter().enumerate() {
+        let line = format!("[{}] {}\n", violation.category, violation);
+        if list.len() + line.len() > 1700 {
+            list.push_str(&format!("...and {} more\n", violations.len() - i));
+            break;
+        }
+        list.push_str(&line);
+    }
+    format!("Your car seems to break the regulations. Found {} issue(s):\n```\n{}```\nThis version has not been saved for the event.", violations.len(), list)
+}
+
+/// Checks the zip `driver` attached against the series' regulations and registers it if it passes,
+/// returning what to reply with. `entry` picks which of the driver's cars this is a new version of,
+/// starting at 1, and `csv` which CSV in the zip is the export if there are several.
+pub async fn submit(ctx: &Context, driver: u64, attachments: &[Attachment], series: &Series, entry: u32, csv: Option<&str>) -> Result<String, CommandError> {
+    let settings = Store::get(ctx).await.read(|data| data.series_settings(&series.id)).await;
+    if !settings.submissions_open {
+        return Ok(format!("Submissions for {} are closed right now!", series.name));
+    }
+    if settings.deadline.is_some_and(|deadline| now() >= deadline) {
+        return Ok(format!("The deadline for round {} of {} has passed!", settings.round, series.name));
+    }
+    if entry == 0 || entry > settings.max_entries {
+        return Ok(format!("{} allows {} entr{} per driver, pick one of those!", series.name, settings.max_entries, if settings.max_entries == 1 { "y" } else { "ies" }));
+    }
+
+    if attachments.len() != 1 {
+        return Ok(format!("Attach 1 file! No more, no less. If your zip submission is too big, get in contact with {} for now.", crate::config::get().contact()));
+    }
+
+    // Nothing about the upload is trusted until it's been checked
+    let attachment = &attachments[0];
+    let limits = Limits::default();
+    let checked = if attachment.size > limits.zip_size {
+        Err(ArchiveError::TooLarge { size: attachment.size, limit: limits.zip_size })
+    } else {
+        archive::check_file_name(&attachment.filename)
+    };
+    if let Err(e) = checked {
+        return Ok(e.to_string());
+    }
+
+    let zip_bytes = match attachment.download().await {
+        Ok(zip_bytes) => zip_bytes,
+        Err(_) => return Ok(String::from("Error encountered while downloading file!")),
+    };
+    let csv = match archive::find_csvs(&zip_bytes, &limits).and_then(|candidates| archive::choose(candidates, csv)) {
+        Ok(csv) => csv,
+        Err(ArchiveError::NoCsv) => return Ok(String::from("Failed to find CSV file in your zip! Did you send the right zip file?")),
+        Err(e @ (ArchiveError::SeveralCsvs { .. } | ArchiveError::NoSuchCsv { .. })) => {
+            let how = if config::get().message_commands {
+                format!("`{} {} csv=<number>`, or the `csv` option of `/submit`,", config::get().command("submit"), series.id)
+            } else {
+                String::from("the `csv` option of `/submit`")
+            };
+            return Ok(format!("{}\nSubmit again with {} to pick the export.", e, how));
+        },
+        Err(e) => return Ok(e.to_string()),
+    };
+
+    check_and_register(ctx, driver, &attachment.filename, series, entry, csv, zip_bytes).await
+}
+
+async fn check_and_register(ctx: &Context, driver: u64, filename: &str, series: &Series, entry: u32, csv: Candidate, zip_bytes: Vec<u8>) -> Result<String, CommandError> {
+    let export = match Export::from_bytes(&csv.bytes) {
+        Ok(export) => export,
+        Err(e) => return Ok(format!("Something went wrong with loading the file! {}", e)),
+    };
+    let car_data = match export.single() {
+        Ok(car_data) => car_data,
+        Err(ExportError::Ambiguous { trims }) => return Ok(format!("Your export contains {} trims:\n{}\nPlease export only the trim you want to enter and submit again!", trims.len(), trims.join("\n"))),
+        Err(e) => return Ok(format!("Something went wrong with loading the file! {}", e)),
+    };
+    let regulations = match RuleSet::from_file(&series.regulations) {
+        Ok(regulations) => regulations,
+        Err(e) => {
+            println!("Failed to load regulations: {:?}", e);
+            return Ok(String::from("Couldn't load the regulations for this series! Please ping any of the EMs for this series"));
+        },
+    };
+    let report = regulations.check(car_data);
+
+    // Every version is kept under its hash, so nothing is ever overwritten
+    let hash = hash(&zip_bytes);
+    let file = format!("{}/{}.zip", series.registration_dir, hash);
+    let verdict = if report.is_ok() {
+        Verdict::Passed
+    } else {
+        Verdict::Rejected { issues: report.violations().iter().map(|violation| format!("[{}] {}", violation.category, violation)).collect() }
+    };
+    let saved = verdict != Verdict::Passed || std::path::Path::new(&file).exists() || save(series, &file, &zip_bytes).is_ok();
+    let failed = || String::from("Seems like your car is good to go! Something went wrong while registering however. Please ping any of the EMs for this series");
+    let recorded = if saved {
+        let recorded = Store::get(ctx).await.update(|data| {
+            // The round may have been locked while the car was being checked
+            let settings = data.series_settings(&series.id);
+            if !settings.submissions_open || settings.deadline.is_some_and(|deadline| now() >= deadline) {
+                return Err(format!("Submissions for {} closed while your car was being checked, so it hasn't been registered!", series.name));
+            }
+            Ok(data.add_submission(Submission {
+                id: 0,
+                series: String::from(&series.id),
+                round: settings.round,
+                driver,
+                entry,
+                car: car_data.label(),
+                csv: csv.path.clone(),
+                filename: String::from(filename),
+                hash,
+                file: if verdict == Verdict::Passed { Some(file) } else { None },
+                submitted_at: now(),
+                verdict: verdict.clone(),
+                restored_from: None,
+            }))
+        }).await;
+        recorded.unwrap_or_else(|_| Err(failed()))
+    } else {
+        Err(failed())
+    };
+
+    let reply = match (verdict, recorded) {
+        (Verdict::Passed, Ok(id)) => {
+            let mut reply = format!("Seems like your car is good to go! Registered it as version #{} of your entry {} for the next {} event, feel free to send in new versions whenever you want!", id, entry, series.name);
+            if let Some(note) = &series.registration_note {
+                reply.push(' ');
+                reply.push_str(note);
+            }
+            reply
+        },
+        (Verdict::Passed, Err(reason)) => reason,
+        (Verdict::Rejected { .. }, _) => format_violations(&report),
+    };
+    Ok(reply)
+}
+
+/// Saves an accepted zip into the series' registration directory. Another submission of the same
+/// zip may be saving it at the same time, so it's written through a workspace of its own.
+fn save(series: &Series, file: &str, zip_bytes: &[u8]) -> std::io::Result<()> {
+    let workspace = Workspace::new_in(&series.registration_dir)?;
+    workspace.write_atomically(file, zip_bytes)
+}
+
+/// Reads the car out of a stored submission.
+pub fn read_car(submission: &Submission) -> Result<CarData, CommandError> {
+    let zip_bytes = std::fs::read(submission.file.as_ref().ok_or("The submission wasn't kept")?)?;
+    let candidates = archive::find_csvs(&zip_bytes, &Limits::default())?;
+    let csv = archive::choose(candidates, Some(&submission.csv).filter(|csv| !csv.is_empty()).map(String::as_str))?;
+    Ok(car_checker::from_bytes(&csv.bytes)?)
+}
+
+/// The SHA-256 of a file, in hex.
+pub fn hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Seconds since the Unix epoch.
+pub fn now() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or_default()
+}
@@ -0,0 +1,137 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+static RUN: OnceLock<String> = OnceLock::new();
+
+const PREFIX: &str = ".workspace-";
+
+/// A scratch directory that belongs to a single task. Every workspace gets its own directory, so
+/// tasks running at the same time never see each other's files, and it's removed again when the
+/// workspace is dropped, whether the task finished, returned an error or panicked.
+pub struct Workspace {
+    path: PathBuf,
+}
+
+impl Workspace {
+    /// Creates a workspace inside `parent`. Files are moved out of it with a rename, so it should
+    /// be on the same filesystem as where they end up.
+    pub fn new_in<P: AsRef<Path>>(parent: P) -> std::io::Result<Self> {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let path = parent.as_ref().join(format!("{}{}-{}", PREFIX, run(), id));
+        std::fs::create_dir_all(&path)?;
+        Ok(Self { path })
+    }
+
+    /// Writes `contents` to `destination` so that it's either fully there or not at all: it's
+    /// written in the workspace first and then renamed into place.
+    pub fn write_atomically<P: AsRef<Path>>(&self, destination: P, contents: &[u8]) -> std::io::Result<()> {
+        let file_name = destination.as_ref().file_name().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "destination has no file name"))?;
+        let scratch = self.path.join(file_name);
+        std::fs::write(&scratch, contents)?;
+        std::fs::rename(&scratch, destination)
+    }
+}
+
+/// Tells this run of the bot apart from earlier ones. The pid alone doesn't, as in a container the
+/// bot usually gets the same one every time it starts.
+fn run() -> &'static str {
+    RUN.get_or_init(|| {
+        let started = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_nanos()).unwrap_or_default();
+        format!("{:x}{:x}", std::process::id(), started)
+    })
+}
+
+/// Removes the workspaces in `parent` that were left behind by an earlier run, like when the bot was
+/// killed while saving a car, returning how many there were.
+pub fn sweep<P: AsRef<Path>>(parent: P) -> std::io::Result<usize> {
+    let entries = match std::fs::read_dir(parent) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    let mut swept = 0;
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name();
+        let run = name.to_str().and_then(|name| name.strip_prefix(PREFIX)).and_then(|rest| rest.split_once('-')).map(|(run, _)| run);
+        if let Some(run) = run {
+            if run != self::run() && entry.file_type()?.is_dir() {
+                std::fs::remove_dir_all(entry.path())?;
+                swept += 1;
+            }
+        }
+    }
+    Ok(swept)
+}
+
+impl Drop for Workspace {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.path) {
+            println!("Failed to clean up {}: {:?}", self.path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parent(test: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rnr_workspace_test_{}_{}", std::process::id(), test))
+    }
+
+    #[test]
+    fn workspaces_are_separate_and_cleaned_up() {
+        let parent = parent("separate");
+        let first = Workspace::new_in(&parent).unwrap();
+        let second = Workspace::new_in(&parent).unwrap();
+        assert_ne!(first.path, second.path);
+
+        let destination = parent.join("car.zip");
+        first.write_atomically(&destination, b"zip").unwrap();
+        assert_eq!(std::fs::read(&destination).unwrap(), b"zip");
+
+        let paths = [first.path.clone(), second.path.clone()];
+        drop(first);
+        drop(second);
+        assert!(paths.iter().all(|path| !path.exists()));
+        std::fs::remove_dir_all(parent).unwrap();
+    }
+
+    #[test]
+    fn cleaned_up_on_panic() {
+        let parent = parent("panic");
+        let path = std::panic::catch_unwind(|| {
+            let workspace = Workspace::new_in(&parent).unwrap();
+            std::fs::write(workspace.path.join("half written"), b"zi").unwrap();
+            panic!("{}", workspace.path.display());
+        }).unwrap_err();
+        let path = PathBuf::from(path.downcast_ref::<String>().unwrap());
+        assert!(!path.exists());
+        std::fs::remove_dir_all(parent).unwrap();
+    }
+
+    #[test]
+    fn stale_workspaces_are_swept() {
+        let parent = parent("sweep");
+        let live = Workspace::new_in(&parent).unwrap();
+        // Left behind by an earlier run that was killed, which may well have had the same pid
+        let stale = parent.join(format!("{}{:x}-0", PREFIX, std::process::id()));
+        std::fs::create_dir_all(&stale).unwrap();
+        std::fs::write(stale.join("car.zip"), b"zi").unwrap();
+        let older = parent.join(format!("{}{}-0", PREFIX, u32::MAX));
+        std::fs::create_dir_all(&older).unwrap();
+        std::fs::write(parent.join("car.zip"), b"zip").unwrap();
+
+        assert_eq!(sweep(&parent).unwrap(), 2);
+        assert!(!stale.exists() && !older.exists());
+        assert!(live.path.exists());
+        assert!(parent.join("car.zip").exists());
+        drop(live);
+        std::fs::remove_dir_all(parent).unwrap();
+    }
+}